@@ -0,0 +1,66 @@
+//! Local hot-seat terminal game.
+//!
+//! Two to four players share the keyboard, optionally against bots. See
+//! [`lava_grid::game::demo::input`] for the key bindings.
+//!
+//! ```text
+//! cargo run --bin demo -- [options]
+//!
+//!   --humans N       players at the keyboard (default 2)
+//!   --bots A,B,..    bot opponents: Random, Greedy or Search (default none)
+//!   --mode M         game mode (default Classic)
+//!   --topology T     Square, EightWay or Hex (default Square)
+//!   --seed S         seed of the game (default random)
+//! ```
+
+use std::process::ExitCode;
+
+use lava_grid::cli::{parse_list, parse_name};
+use lava_grid::config::game::GameRules;
+use lava_grid::game::demo::game_loop::{DemoOptions, Seat, run_game_loop};
+use lava_grid::game::types::{AgentKind, GameMode};
+
+fn main() -> ExitCode {
+    let options = match parse_options(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("demo: {}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+    match run_game_loop(options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("demo: terminal error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Parse the command-line arguments (without the program name).
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<DemoOptions, String> {
+    let mut humans = 2;
+    let mut bots: Vec<AgentKind> = Vec::new();
+    let mut mode = GameMode::Classic;
+    let mut rules = GameRules::default();
+    let mut seed: u64 = rand::random();
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {}", flag));
+        match flag.as_str() {
+            "--humans" => humans = value()?.parse().map_err(|e| format!("--humans: {}", e))?,
+            "--bots" => bots = parse_list(&value()?)?,
+            "--mode" => mode = parse_name(&value()?)?,
+            "--topology" => rules.topology = parse_name(&value()?)?,
+            "--seed" => seed = value()?.parse().map_err(|e| format!("--seed: {}", e))?,
+            _ => return Err(format!("unknown option {}", flag)),
+        }
+    }
+
+    let seats: Vec<Seat> = std::iter::repeat_n(Seat::Human, humans)
+        .chain(bots.into_iter().map(Seat::Bot))
+        .collect();
+    if !(2..=4).contains(&seats.len()) {
+        return Err(format!("a game needs 2 to 4 players, got {}", seats.len()));
+    }
+    Ok(DemoOptions { seats, mode, rules, seed })
+}
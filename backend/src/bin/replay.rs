@@ -0,0 +1,45 @@
+//! Replay verifier.
+//!
+//! Plays recorded games again on the game core and checks that every turn leads to the
+//! recorded state, to investigate disputed games and reproduce crashes.
+//!
+//! ```text
+//! cargo run --bin replay -- FILE...
+//! ```
+
+use std::path::Path;
+use std::process::ExitCode;
+
+use lava_grid::game::replay::Replay;
+
+fn main() -> ExitCode {
+    let files: Vec<String> = std::env::args().skip(1).collect();
+    if files.is_empty() {
+        eprintln!("replay: no replay file given");
+        return ExitCode::FAILURE;
+    }
+
+    let mut status = ExitCode::SUCCESS;
+    for file in &files {
+        match Replay::load(Path::new(file)) {
+            Err(e) => {
+                eprintln!("{}: cannot read replay: {}", file, e);
+                status = ExitCode::FAILURE;
+            }
+            Ok(replay) => match replay.verify() {
+                Ok(state) => println!(
+                    "{}: ok, {:?} game of {} turns, {:?}",
+                    file,
+                    replay.mode,
+                    replay.turns.len(),
+                    state.outcome().map(|outcome| outcome.result),
+                ),
+                Err(e) => {
+                    eprintln!("{}: {}", file, e);
+                    status = ExitCode::FAILURE;
+                }
+            },
+        }
+    }
+    status
+}
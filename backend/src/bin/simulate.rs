@@ -0,0 +1,104 @@
+//! Headless self-play simulator.
+//!
+//! Plays games between bot agents on the game core, in every game mode (or the chosen
+//! ones), and prints win rates by spawn order, average game length and draw rates.
+//!
+//! ```text
+//! cargo run --release --bin simulate -- [options]
+//!
+//!   --games N        games per mode (default 1000)
+//!   --agents A,B,..  agent of each seat: Random, Greedy or Search (default Greedy,Greedy)
+//!   --modes M,N,..   modes to simulate (default: every mode)
+//!   --rules FILE     JSON rule set; missing fields keep their default value
+//!   --seed S         seed of the first game of each mode (default 0)
+//!   --max-turns N    turns after which a game is stopped (default 200)
+//! ```
+
+use std::process::ExitCode;
+
+use serde_json::Value;
+
+use lava_grid::cli::parse_list;
+use lava_grid::config::game::GameRules;
+use lava_grid::game::modes::available_modes;
+use lava_grid::game::simulation::simulate_mode;
+use lava_grid::game::types::{AgentKind, GameMode};
+
+/// Options of a simulation run.
+struct Options {
+    games: u32,
+    seats: Vec<AgentKind>,
+    modes: Vec<GameMode>,
+    rules: GameRules,
+    seed: u64,
+    max_turns: u32,
+}
+
+fn main() -> ExitCode {
+    let options = match parse_options(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("simulate: {}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Modes are independent: simulate them in parallel.
+    let reports = std::thread::scope(|scope| {
+        let handles: Vec<_> = options.modes.iter()
+            .map(|&mode| {
+                let options = &options;
+                scope.spawn(move || {
+                    simulate_mode(&options.rules, mode, &options.seats, options.games, options.seed, options.max_turns)
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().expect("simulation thread panicked")).collect::<Vec<_>>()
+    });
+    for report in reports {
+        println!("{}", report);
+    }
+    ExitCode::SUCCESS
+}
+
+/// Parse the command-line arguments (without the program name).
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        games: 1000,
+        seats: vec![AgentKind::Greedy, AgentKind::Greedy],
+        modes: available_modes(),
+        rules: GameRules::default(),
+        seed: 0,
+        max_turns: 200,
+    };
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {}", flag));
+        match flag.as_str() {
+            "--games" => options.games = value()?.parse().map_err(|e| format!("--games: {}", e))?,
+            "--agents" => options.seats = parse_list(&value()?)?,
+            "--modes" => options.modes = parse_list(&value()?)?,
+            "--rules" => options.rules = load_rules(&value()?)?,
+            "--seed" => options.seed = value()?.parse().map_err(|e| format!("--seed: {}", e))?,
+            "--max-turns" => options.max_turns = value()?.parse().map_err(|e| format!("--max-turns: {}", e))?,
+            _ => return Err(format!("unknown option {}", flag)),
+        }
+    }
+    if options.seats.len() < 2 {
+        return Err("at least two agents are needed".to_string());
+    }
+    Ok(options)
+}
+
+/// Load a rule set from a JSON file, on top of the default rules.
+fn load_rules(path: &str) -> Result<GameRules, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let overrides: Value = serde_json::from_str(&text).map_err(|e| format!("{}: {}", path, e))?;
+    let Value::Object(overrides) = overrides else {
+        return Err(format!("{}: the rule set must be a JSON object", path));
+    };
+    let mut rules = serde_json::to_value(GameRules::default()).map_err(|e| e.to_string())?;
+    if let Value::Object(fields) = &mut rules {
+        fields.extend(overrides);
+    }
+    serde_json::from_value(rules).map_err(|e| format!("{}: {}", path, e))
+}
@@ -0,0 +1,14 @@
+//! Command-line helpers shared by the binaries in `src/bin/`.
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Parse an enum variant from its name.
+pub fn parse_name<T: DeserializeOwned>(name: &str) -> Result<T, String> {
+    serde_json::from_value(Value::String(name.trim().to_string())).map_err(|_| format!("unknown value {}", name))
+}
+
+/// Parse a comma-separated list of enum variant names.
+pub fn parse_list<T: DeserializeOwned>(list: &str) -> Result<Vec<T>, String> {
+    list.split(',').map(parse_name).collect()
+}
@@ -1,14 +1,227 @@
-/// Game configuration constants.
-/// 
-/// This module defines the main gameplay parameters such as turn duration,
-/// mode selection duration, and grid dimensions.
-pub const TURN_DURATION: u64 = 8; // Duration of a turn in seconds.
+//! Game configuration.
+//! 
+//! This module defines the default gameplay parameters such as turn duration,
+//! mode selection duration, and grid dimensions, and the [`GameRules`] struct
+//! that carries them at runtime so each game can use its own rule set.
 
-/// Duration (in seconds) for players to choose the game mode before the game starts.
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+
+use crate::game::types::{GameMode, BlastShape, HitEffect, ShotRange, ItemKind, SpawnStrategy};
+use crate::game::topology::Topology;
+
+/// Default duration of a turn in seconds.
+pub const TURN_DURATION: u64 = 8;
+
+/// Default duration (in seconds) for players to choose the game mode before the game starts.
 pub const MODE_CHOICE_DURATION: u64 = 10;
 
-/// Number of rows in the game grid.
+/// Default duration (in seconds) for players to pick their spawn tile in modes with the
+/// draft spawn strategy.
+pub const SPAWN_DRAFT_DURATION: u64 = 10;
+
+/// Default number of rows in the game grid.
 pub const GRID_ROW: usize = 5;
 
-/// Number of columns in the game grid.
-pub const GRID_COL: usize = 5;
\ No newline at end of file
+/// Default number of columns in the game grid.
+pub const GRID_COL: usize = 5;
+
+/// Default minimum number of cannonballs placed on the grid at the start of a game.
+pub const INITIAL_CANNONBALLS_MIN: usize = 1;
+
+/// Default maximum number of cannonballs placed on the grid at the start of a game.
+pub const INITIAL_CANNONBALLS_MAX: usize = 3;
+
+/// Default number of tiles broken (or cracked, depending on the mode) at the end of each turn.
+pub const TILES_BROKEN_PER_TURN: usize = 1;
+
+/// Default number of turns between two cannonball respawns.
+pub const CANNONBALL_RESPAWN_INTERVAL: u32 = 3;
+
+/// Default number of cannonballs spawned at each respawn.
+pub const CANNONBALL_RESPAWN_COUNT: usize = 1;
+
+/// Default number of turns between two shrinks of the ring in the shrinking ring mode.
+pub const RING_SHRINK_INTERVAL: u32 = 3;
+
+/// Default chance (in percent) for a broken tile to crack each intact neighbour sharing an
+/// edge with it in the spreading lava mode.
+pub const LAVA_SPREAD_CHANCE: u8 = 25;
+
+/// Default number of teams formed by matchmaking for team modes.
+pub const TEAM_COUNT: u8 = 2;
+
+/// Default number of turns after which a scoring mode ends.
+pub const TURN_LIMIT: u32 = 30;
+
+/// Default score that ends a scoring mode as soon as a player reaches it.
+pub const TARGET_SCORE: u32 = 10;
+
+/// Default vision radius (in moves on the board) of players in the fog of war mode.
+pub const VISION_RADIUS: usize = 2;
+
+/// Chance (in percent) for each tile of a generated map to hold an obstacle (a hole or a wall).
+pub const MAP_OBSTACLE_PERCENT: u32 = 20;
+
+/// Default minimum distance (in moves) between a spawning cannonball and any living player.
+pub const CANNONBALL_MIN_DISTANCE: usize = 2;
+
+/// Default number of turns between two item spawns (when the mode has items).
+pub const ITEM_SPAWN_INTERVAL: u32 = 5;
+
+/// Items spawning by default in the king of the hill mode, the only mode with items under
+/// the default rules.
+pub const KING_OF_THE_HILL_ITEMS: [ItemKind; 4] = [ItemKind::Shield, ItemKind::Dash, ItemKind::RepairKit, ItemKind::Freeze];
+
+/// Directory where the replay of every finished game is written, one JSON file per game ID.
+pub const REPLAY_DIR: &str = "replays";
+
+/// When new cannonballs or items appear on the grid during a game.
+///
+/// Spawns happen at the end of a turn, once tiles have broken, and only on free solid or
+/// ice tiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "schedule", content = "data")]
+pub enum SpawnSchedule {
+    /// Nothing spawns during the game.
+    Never,
+    /// Spawn `count` objects at the end of every `turns`-th turn.
+    Every { turns: u32, count: usize },
+    /// Spawn `count` objects whenever fewer than `min` lie on the grid.
+    BelowMinimum { min: usize, count: usize },
+}
+
+impl SpawnSchedule {
+    /// Number of objects to spawn at the end of `turn`, given how many lie on the grid.
+    pub fn spawn_count(self, turn: u32, on_grid: usize) -> usize {
+        match self {
+            SpawnSchedule::Never => 0,
+            SpawnSchedule::Every { turns, count } => {
+                if turns > 0 && turn.is_multiple_of(turns) { count } else { 0 }
+            }
+            SpawnSchedule::BelowMinimum { min, count } => {
+                if on_grid < min { count } else { 0 }
+            }
+        }
+    }
+}
+
+/// Rule set of a game, chosen when the game is created and carried by its state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameRules {
+    /// Number of rows in the game grid.
+    pub grid_rows: usize,
+    /// Number of columns in the game grid.
+    pub grid_cols: usize,
+    /// How the tiles of the grid connect: move directions, neighbours and distances.
+    pub topology: Topology,
+    /// Minimum number of cannonballs placed at the start of the game.
+    pub initial_cannonballs_min: usize,
+    /// Maximum number of cannonballs placed at the start of the game.
+    pub initial_cannonballs_max: usize,
+    /// Number of tiles broken (or cracked) at the end of each turn.
+    pub tiles_broken_per_turn: usize,
+    /// Turns between two shrinks of the ring in the shrinking ring mode (0 to never shrink).
+    pub ring_shrink_interval: u32,
+    /// Chance (in percent, 100 for a fixed rule) for each broken tile to set fire to each
+    /// intact neighbouring tile each turn in the spreading lava mode.
+    pub lava_spread_chance: u8,
+    /// Number of teams matchmaking splits players into (below 2, no teams are formed).
+    pub team_count: u8,
+    /// Whether cannonballs hurt the shooter's teammates in team modes.
+    pub friendly_fire: bool,
+    /// Number of turns after which a scoring mode ends.
+    pub turn_limit: u32,
+    /// Score that ends a scoring mode as soon as a player reaches it.
+    pub target_score: u32,
+    /// How far players see (in moves on the board's topology) in the fog of war mode.
+    pub vision_radius: usize,
+    /// Cannonball respawn schedule used by modes without their own.
+    pub cannonball_schedule: SpawnSchedule,
+    /// Per-mode cannonball respawn schedules, overriding `cannonball_schedule`.
+    pub mode_cannonball_schedules: BTreeMap<GameMode, SpawnSchedule>,
+    /// Minimum distance (in moves on the board's topology) between a spawning cannonball and
+    /// any living player.
+    pub cannonball_min_distance: usize,
+    /// How players are placed at the start of the game.
+    pub spawn_strategy: SpawnStrategy,
+    /// Per-mode spawn strategies, overriding `spawn_strategy`.
+    pub mode_spawn_strategies: BTreeMap<GameMode, SpawnStrategy>,
+    /// Tiles broken by a cannonball around its target.
+    pub blast_shape: BlastShape,
+    /// Effect of a blast on the players standing in it.
+    pub hit_effect: HitEffect,
+    /// Maximum shooting distance from the shooter (`None` for the whole grid).
+    pub shot_range: Option<ShotRange>,
+    /// Whether other players standing between the shooter and the target block the shot.
+    pub shot_line_of_sight: bool,
+    /// Items that can spawn in modes without their own pool (empty for no items).
+    pub item_pool: Vec<ItemKind>,
+    /// Per-mode item pools, overriding `item_pool` (by default, king of the hill spawns every
+    /// item kind).
+    pub mode_item_pools: BTreeMap<GameMode, Vec<ItemKind>>,
+    /// When items spawn, drawing uniformly from the mode's pool.
+    pub item_schedule: SpawnSchedule,
+    /// Duration of a turn in seconds.
+    pub turn_duration_secs: u64,
+    /// Duration of the mode choice phase in seconds.
+    pub mode_choice_duration_secs: u64,
+    /// Duration of the spawn draft in seconds, held after the mode choice in modes with the
+    /// draft spawn strategy.
+    pub spawn_draft_duration_secs: u64,
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        Self {
+            grid_rows: GRID_ROW,
+            grid_cols: GRID_COL,
+            topology: Topology::Square,
+            initial_cannonballs_min: INITIAL_CANNONBALLS_MIN,
+            initial_cannonballs_max: INITIAL_CANNONBALLS_MAX,
+            tiles_broken_per_turn: TILES_BROKEN_PER_TURN,
+            ring_shrink_interval: RING_SHRINK_INTERVAL,
+            lava_spread_chance: LAVA_SPREAD_CHANCE,
+            team_count: TEAM_COUNT,
+            friendly_fire: false,
+            turn_limit: TURN_LIMIT,
+            target_score: TARGET_SCORE,
+            vision_radius: VISION_RADIUS,
+            cannonball_schedule: SpawnSchedule::Every {
+                turns: CANNONBALL_RESPAWN_INTERVAL,
+                count: CANNONBALL_RESPAWN_COUNT,
+            },
+            mode_cannonball_schedules: BTreeMap::new(),
+            cannonball_min_distance: CANNONBALL_MIN_DISTANCE,
+            spawn_strategy: SpawnStrategy::MaxMin,
+            mode_spawn_strategies: BTreeMap::new(),
+            blast_shape: BlastShape::Single,
+            hit_effect: HitEffect::Eliminate,
+            shot_range: None,
+            shot_line_of_sight: false,
+            item_pool: Vec::new(),
+            mode_item_pools: BTreeMap::from([(GameMode::KingOfTheHill, KING_OF_THE_HILL_ITEMS.to_vec())]),
+            item_schedule: SpawnSchedule::Every { turns: ITEM_SPAWN_INTERVAL, count: 1 },
+            turn_duration_secs: TURN_DURATION,
+            mode_choice_duration_secs: MODE_CHOICE_DURATION,
+            spawn_draft_duration_secs: SPAWN_DRAFT_DURATION,
+        }
+    }
+}
+
+impl GameRules {
+    /// Cannonball respawn schedule of the given mode.
+    pub fn cannonball_schedule_for(&self, mode: GameMode) -> SpawnSchedule {
+        self.mode_cannonball_schedules.get(&mode).copied().unwrap_or(self.cannonball_schedule)
+    }
+
+    /// Spawn strategy of the given mode.
+    pub fn spawn_strategy_for(&self, mode: GameMode) -> SpawnStrategy {
+        self.mode_spawn_strategies.get(&mode).copied().unwrap_or(self.spawn_strategy)
+    }
+
+    /// Items that can spawn in the given mode.
+    pub fn item_pool_for(&self, mode: GameMode) -> &[ItemKind] {
+        self.mode_item_pools.get(&mode).unwrap_or(&self.item_pool)
+    }
+}
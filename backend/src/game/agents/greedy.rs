@@ -0,0 +1,33 @@
+//! Greedy survival agent: the best action for the next turn only.
+
+use crate::game::types::{AgentKind, Direction};
+use crate::game::state::GameState;
+use crate::server::game_session::messages::PlayerAction;
+use super::{Agent, agent_rng, evaluate, imagine, legal_actions};
+
+/// Simulates each valid action for one turn, assuming the other players stay in place, and
+/// keeps the one leading to the best [`evaluate`] score (the first one on ties, so staying
+/// wins when nothing is better).
+pub struct GreedyAgent;
+
+impl Agent for GreedyAgent {
+    fn kind(&self) -> AgentKind {
+        AgentKind::Greedy
+    }
+
+    fn choose_action(&self, view: &GameState, me: usize) -> PlayerAction {
+        let id = view.players[me].id;
+        let mut rng = agent_rng(view, me, 0);
+        let world = imagine(view, &mut rng);
+
+        let mut best: Option<(i32, PlayerAction)> = None;
+        for action in legal_actions(view, me) {
+            let (next, _) = world.step(&[(id, action.clone())]);
+            let score = evaluate(&next, me);
+            if best.as_ref().is_none_or(|(s, _)| score > *s) {
+                best = Some((score, action));
+            }
+        }
+        best.map_or(PlayerAction::Move(Direction::Stay), |(_, action)| action)
+    }
+}
@@ -0,0 +1,162 @@
+//! Bot players.
+//!
+//! Each agent implements [`Agent`] in its own module and is listed once in [`AGENTS`]. A
+//! game session seats an agent in place of a human for every
+//! [`PlayerInfo`](crate::server::matchmaking::types::PlayerInfo) with a `bot` kind, and
+//! submits its action each turn like a client would.
+//!
+//! Like human players, bots only see their seat's part of the game (see [`bot_action`]).
+//!
+//! Agents are stateless: every random choice draws from a generator derived from the
+//! game seed, the turn and the player (see [`agent_rng`]), so a game with bots is as
+//! reproducible as any other. When they simulate turns ahead, agents reseed the simulated
+//! state's generator (see [`imagine`]) so they cannot foresee the game's random events.
+
+pub mod random;
+pub mod greedy;
+pub mod search;
+
+use crate::game::types::{AgentKind, Cell, Direction, ItemKind, ItemUse, Position};
+use crate::game::state::GameState;
+use crate::game::rng::GameRng;
+use crate::game::entities::legal_targets;
+use crate::game::view::Vision;
+use rand::RngCore;
+use crate::server::game_session::messages::PlayerAction;
+
+pub use random::RandomAgent;
+pub use greedy::GreedyAgent;
+pub use search::SearchAgent;
+
+/// Score of a state where the player has been eliminated.
+pub const LOSS: i32 = -100_000;
+
+/// Score of a state where no opponent is left.
+pub const WIN: i32 = 100_000;
+
+/// Decision logic of a bot player.
+pub trait Agent: Sync {
+    /// Identifier of the agent, as seated in a game.
+    fn kind(&self) -> AgentKind;
+
+    /// Action of the player at index `me` in `view.players` for the current turn.
+    ///
+    /// The action is valid for `view` whenever the player is alive (staying in place
+    /// otherwise).
+    fn choose_action(&self, view: &GameState, me: usize) -> PlayerAction;
+}
+
+/// All built-in agents.
+pub static AGENTS: &[&dyn Agent] = &[&RandomAgent, &GreedyAgent, &SearchAgent::DEFAULT];
+
+/// Agent of the given kind.
+///
+/// # Panics
+/// Panics if the agent was not added to [`AGENTS`].
+pub fn agent(kind: AgentKind) -> &'static dyn Agent {
+    *AGENTS.iter()
+        .find(|a| a.kind() == kind)
+        .unwrap_or_else(|| panic!("agent {:?} is not registered", kind))
+}
+
+/// Action of the bot of the given kind playing player `player_id`, chosen from what that
+/// player can see (see [`Vision::redact`]). Eliminated or unknown players stay in place.
+pub fn bot_action(kind: AgentKind, state: &GameState, player_id: u8) -> PlayerAction {
+    let view = Vision::of(state, Some(player_id)).redact(state);
+    match view.players.iter().position(|p| p.id == player_id && p.is_alive) {
+        Some(me) => agent(kind).choose_action(&view, me),
+        None => PlayerAction::Move(Direction::Stay),
+    }
+}
+
+/// Every valid action of the player at index `me`, staying in place first.
+///
+/// Empty if the player is eliminated.
+pub fn legal_actions(state: &GameState, me: usize) -> Vec<PlayerAction> {
+    let player = &state.players[me];
+    let topology = state.rules.topology;
+    let directions = topology.directions();
+    let rows = state.grid.len();
+    let cols = state.grid.first().map_or(0, |row| row.len());
+
+    let mut actions = vec![PlayerAction::Move(Direction::Stay)];
+    actions.extend(directions.iter().map(|&d| PlayerAction::Move(d)));
+    actions.extend(legal_targets(state, me).into_iter().map(|t| PlayerAction::Shoot { x: t.x, y: t.y }));
+    for (i, kind) in player.inventory.iter().enumerate() {
+        // Several items of the same kind offer the same uses.
+        if player.inventory[..i].contains(kind) {
+            continue;
+        }
+        let uses: Vec<ItemUse> = match kind {
+            ItemKind::Shield => vec![ItemUse::Shield],
+            ItemKind::Dash => directions.iter().map(|&d| ItemUse::Dash(d)).collect(),
+            ItemKind::RepairKit => topology.neighbours(player.pos, rows, cols)
+                .into_iter()
+                .map(|p| ItemUse::RepairKit { x: p.x, y: p.y })
+                .collect(),
+            ItemKind::Freeze => state.players.iter()
+                .map(|p| ItemUse::Freeze { player_id: p.id })
+                .collect(),
+        };
+        actions.extend(uses.into_iter().map(PlayerAction::UseItem));
+    }
+
+    actions.retain(|action| state.validate_action(player.id, action).is_ok());
+    actions
+}
+
+/// Heuristic value of `state` for the player at index `me`; higher is better.
+///
+/// Being eliminated is worst and being the last side standing best. In between, the
+/// score rewards points (scoring modes), safe tiles around the player, ammunition and items,
+/// and penalizes living opponents and standing on a cracked tile.
+pub fn evaluate(state: &GameState, me: usize) -> i32 {
+    let player = &state.players[me];
+    if !player.is_alive {
+        return LOSS;
+    }
+    let opponents = state.players.iter().enumerate()
+        .filter(|&(i, p)| i != me && p.is_alive && (p.team_id.is_none() || p.team_id != player.team_id))
+        .count() as i32;
+    if opponents == 0 {
+        return WIN;
+    }
+
+    let rows = state.grid.len();
+    let cols = state.grid.first().map_or(0, |row| row.len());
+    let safe_exits = state.rules.topology.neighbours(player.pos, rows, cols).iter()
+        .filter(|p| is_safe(state.grid[p.y][p.x]))
+        .count() as i32;
+    let standing_on_crack = state.grid[player.pos.y][player.pos.x] == Cell::Cracked;
+
+    1_000 * player.score as i32
+        + 30 * safe_exits
+        + 20 * player.cannonball_count.min(3) as i32
+        + 10 * player.inventory.len() as i32
+        - 100 * opponents
+        - if standing_on_crack { 80 } else { 0 }
+        - nearest_cannonball(state, player.pos).map_or(0, |d| d as i32)
+}
+
+/// Whether a cell can be stood on for more than a turn.
+fn is_safe(cell: Cell) -> bool {
+    cell.is_walkable() && !matches!(cell, Cell::Broken | Cell::Cracked)
+}
+
+/// Distance to the nearest cannonball lying on the grid, if any.
+fn nearest_cannonball(state: &GameState, pos: Position) -> Option<usize> {
+    state.cannonballs.iter().map(|c| state.rules.topology.distance(pos, c.pos)).min()
+}
+
+/// Generator for the decisions of the player at index `me` this turn, derived from the game
+/// seed; `salt` tells apart several generators of the same decision.
+pub fn agent_rng(state: &GameState, me: usize, salt: u64) -> GameRng {
+    GameRng::new(state.seed ^ ((state.turn as u64) << 32) ^ ((me as u64) << 16) ^ salt.rotate_left(48))
+}
+
+/// Copy of `state` for simulating turns ahead, with its generator reseeded from `rng`.
+pub fn imagine(state: &GameState, rng: &mut GameRng) -> GameState {
+    let mut copy = state.clone();
+    copy.rng = GameRng::new(rng.next_u64());
+    copy
+}
@@ -0,0 +1,25 @@
+//! Random agent: any valid action, uniformly.
+
+use rand::seq::IndexedRandom;
+
+use crate::game::types::{AgentKind, Direction};
+use crate::game::state::GameState;
+use crate::server::game_session::messages::PlayerAction;
+use super::{Agent, agent_rng, legal_actions};
+
+/// Picks one of the player's valid actions uniformly at random.
+pub struct RandomAgent;
+
+impl Agent for RandomAgent {
+    fn kind(&self) -> AgentKind {
+        AgentKind::Random
+    }
+
+    fn choose_action(&self, view: &GameState, me: usize) -> PlayerAction {
+        let mut rng = agent_rng(view, me, 0);
+        legal_actions(view, me)
+            .choose(&mut rng)
+            .cloned()
+            .unwrap_or(PlayerAction::Move(Direction::Stay))
+    }
+}
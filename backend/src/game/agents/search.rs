@@ -0,0 +1,73 @@
+//! Search agent: Monte Carlo lookahead over a few turns.
+
+use rand::seq::IndexedRandom;
+
+use crate::game::types::{AgentKind, Direction};
+use crate::game::state::GameState;
+use crate::game::rng::GameRng;
+use crate::server::game_session::messages::PlayerAction;
+use super::{Agent, agent_rng, evaluate, imagine, legal_actions};
+
+/// Rates each valid action by playing it out `rollouts` times over `depth` turns, every
+/// player (including itself after the first turn) acting at random, and keeps the action
+/// with the best total [`evaluate`] score at the end of the rollouts.
+///
+/// Rollouts stop early once the player is eliminated or the game is over. Every action is
+/// rated on rollouts drawn from the same seeds, which keeps the comparison fair.
+pub struct SearchAgent {
+    /// Number of turns simulated per rollout.
+    pub depth: u32,
+    /// Number of rollouts per action.
+    pub rollouts: u32,
+}
+
+impl SearchAgent {
+    /// Search settings of the built-in agent.
+    pub const DEFAULT: SearchAgent = SearchAgent { depth: 3, rollouts: 8 };
+}
+
+impl Agent for SearchAgent {
+    fn kind(&self) -> AgentKind {
+        AgentKind::Search
+    }
+
+    fn choose_action(&self, view: &GameState, me: usize) -> PlayerAction {
+        let mut best: Option<(i64, PlayerAction)> = None;
+        for action in legal_actions(view, me) {
+            let mut total = 0;
+            for rollout in 0..self.rollouts {
+                let mut rng = agent_rng(view, me, 1 + rollout as u64);
+                total += self.rollout(view, me, &action, &mut rng) as i64;
+            }
+            if best.as_ref().is_none_or(|(s, _)| total > *s) {
+                best = Some((total, action));
+            }
+        }
+        best.map_or(PlayerAction::Move(Direction::Stay), |(_, action)| action)
+    }
+}
+
+impl SearchAgent {
+    /// Play `first` then random actions for `depth` turns and rate the resulting state.
+    fn rollout(&self, view: &GameState, me: usize, first: &PlayerAction, rng: &mut GameRng) -> i32 {
+        let mut state = imagine(view, rng);
+        for turn in 0..self.depth {
+            let actions: Vec<(u8, PlayerAction)> = (0..state.players.len())
+                .filter(|&i| state.players[i].is_alive)
+                .filter_map(|i| {
+                    let action = if turn == 0 && i == me {
+                        first.clone()
+                    } else {
+                        legal_actions(&state, i).choose(rng)?.clone()
+                    };
+                    Some((state.players[i].id, action))
+                })
+                .collect();
+            state = state.step(&actions).0;
+            if !state.players[me].is_alive || state.outcome().is_some() {
+                break;
+            }
+        }
+        evaluate(&state, me)
+    }
+}
@@ -1,56 +1,332 @@
-//! Standalone game loop for local testing/demo.
+//! Hot-seat game loop of the terminal client.
 //!
-//! This module provides an interactive loop for playing the game in the terminal.
-
-use crate::game::systems::{move_player, apply_rules, print_player_state, print_grid};
-use crate::game::types::Direction;
-use crate::game::state::GameState;
+//! Every turn, each living human player picks an action in seat order (invalid actions are
+//! refused with the reason and asked again) and each bot picks its own; the turn is then
+//! resolved with [`GameState::step`] and its events are shown with the next turn. When only
+//! bots are left, turns play out on their own.
 
 use std::io::{self, Write};
+use std::time::Duration;
+
+use crossterm::{cursor, event, execute, terminal};
+use crossterm::event::Event;
+
+use crate::config::game::GameRules;
+use crate::game::agents::bot_action;
+use crate::game::events::GameEvent;
+use crate::game::rng::{GameRng, RngStream};
+use crate::game::state::{GameResult, GameState};
+use crate::game::topology::Topology;
+use crate::game::types::{AgentKind, Direction, GameMode, ItemUse, Position};
+use crate::server::game_session::messages::PlayerAction;
+use crate::server::matchmaking::types::{PlayerInfo, assign_teams};
+use super::input::{Command, command_for};
+use super::render::render_frame;
+
+/// Delay between two turns when only bots are playing.
+const BOT_TURN_DELAY: Duration = Duration::from_millis(500);
 
-/// Prompt the user for a movement direction.
-fn get_player_input() -> Direction {
-    print!("Enter direction (← ↑ ↓ → or Space), then press Enter: ");
-    io::stdout().flush().unwrap();
+/// Who plays a seat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Seat {
+    /// A player at the keyboard.
+    Human,
+    /// A bot of the given kind.
+    Bot(AgentKind),
+}
+
+/// Settings of a local game.
+#[derive(Debug, Clone)]
+pub struct DemoOptions {
+    /// Seats in spawn order (two to four).
+    pub seats: Vec<Seat>,
+    /// Mode of the game.
+    pub mode: GameMode,
+    /// Rule set of the game.
+    pub rules: GameRules,
+    /// Seed of the game.
+    pub seed: u64,
+}
 
-    let mut input = String::new();
-    io::stdin().read_line(&mut input).unwrap();
+/// Terminal in raw mode on the alternate screen, restored when dropped (even on panic).
+struct RawTerminal;
 
-    match input.trim() {
-        "\x1b[D" => Direction::Left,
-        "\x1b[C" => Direction::Right,
-        "\x1b[A" => Direction::Up,
-        "\x1b[B" => Direction::Down,
-        _ => Direction::Stay,
+impl RawTerminal {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(RawTerminal)
+    }
+
+    /// Replace the screen content with `frame`.
+    fn draw(&self, frame: &str) -> io::Result<()> {
+        let mut stdout = io::stdout();
+        execute!(stdout, cursor::MoveTo(0, 0), terminal::Clear(terminal::ClearType::All))?;
+        // Raw mode does not return the carriage on line feeds.
+        stdout.write_all(frame.replace('\n', "\r\n").as_bytes())?;
+        stdout.flush()
+    }
+
+    /// Wait for the next key press bound to a command.
+    fn next_command(&self) -> io::Result<Command> {
+        loop {
+            if let Event::Key(key) = event::read()?
+                && let Some(command) = command_for(key)
+            {
+                return Ok(command);
+            }
+        }
     }
 }
 
-/// Run the main game loop for a single player.
-pub fn run_game_loop() {
-    let player_id = 0;
-    // Initialize the game state with a single player.
-    let mut game_state = GameState::new(5, 5, 1); // TODO: Update for multi-player if needed
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Player infos of the seats, with teams assigned as matchmaking would.
+fn seat_infos(options: &DemoOptions) -> Vec<PlayerInfo> {
+    let mut infos: Vec<PlayerInfo> = options.seats.iter().enumerate()
+        .map(|(i, seat)| PlayerInfo {
+            id: format!("local-{}", i + 1),
+            username: match seat {
+                Seat::Human => format!("Player {}", i + 1),
+                Seat::Bot(kind) => format!("{:?} bot", kind),
+            },
+            team_id: None,
+            spawn_pick: None,
+            bot: match seat {
+                Seat::Human => None,
+                Seat::Bot(kind) => Some(*kind),
+            },
+        })
+        .collect();
+    assign_teams(&mut infos, options.rules.team_count, &mut GameRng::stream(options.seed, RngStream::Teams));
+    infos
+}
+
+/// Play a local game until it is over or a player quits.
+pub fn run_game_loop(options: DemoOptions) -> io::Result<()> {
+    let mut state = GameState::new(options.rules.clone(), seat_infos(&options), options.mode, options.seed);
+    let terminal = RawTerminal::enter()?;
+    let mut last_events: Vec<GameEvent> = Vec::new();
+
+    loop {
+        let mut messages = event_lines(&last_events);
+        let shots = shot_tiles(&last_events);
+        if let Some(outcome) = state.outcome() {
+            messages.push(result_line(&outcome.result));
+            messages.push("Press any key to leave.".to_string());
+            terminal.draw(&render_frame(&state, &shots, None, &messages))?;
+            terminal.next_command()?;
+            return Ok(());
+        }
+
+        let humans_alive = state.players.iter()
+            .any(|p| p.is_alive && options.seats[p.id as usize - 1] == Seat::Human);
+        if !humans_alive {
+            messages.push("Bots are playing. Esc to quit.".to_string());
+            terminal.draw(&render_frame(&state, &shots, None, &messages))?;
+            if event::poll(BOT_TURN_DELAY)?
+                && let Event::Key(key) = event::read()?
+                && matches!(command_for(key), Some(Command::Cancel | Command::Quit))
+            {
+                return Ok(());
+            }
+        }
 
-    println!("Game start!");
-    print_player_state(&game_state.players[0]);
-    print_grid(&game_state.grid, &game_state.players, &game_state.cannonballs);
+        let mut actions = Vec::new();
+        for (index, player) in state.players.iter().enumerate() {
+            if !player.is_alive {
+                continue;
+            }
+            let action = match options.seats[player.id as usize - 1] {
+                Seat::Bot(kind) => bot_action(kind, &state, player.id),
+                Seat::Human => match ask_action(&terminal, &state, index, &shots, &messages)? {
+                    Some(action) => action,
+                    None => return Ok(()),
+                },
+            };
+            actions.push((player.id, action));
+        }
+
+        let (next, events) = state.step(&actions);
+        state = next;
+        last_events = events;
+    }
+}
 
+/// Ask the human player at `index` for a valid action, or `None` if they quit. `shots`
+/// are the tiles shot at on the last turn.
+fn ask_action(
+    terminal: &RawTerminal,
+    state: &GameState,
+    index: usize,
+    shots: &[Position],
+    messages: &[String],
+) -> io::Result<Option<PlayerAction>> {
+    let player = &state.players[index];
+    let mut error: Option<String> = None;
     loop {
-        let direction = get_player_input();
-        move_player(&mut game_state, player_id, direction);
+        let mut lines = messages.to_vec();
+        lines.push(format!(
+            "P{} ({}), your action: arrows/WASD/QEZC move, space stay, F shoot, H shield, G dash, R repair, X freeze, Esc quit.",
+            player.id, player.username,
+        ));
+        lines.extend(error.take());
+        terminal.draw(&render_frame(state, shots, None, &lines))?;
 
-        // Apply rules for the current player (e.g., pickup, death).
-        apply_rules(&mut game_state, player_id);
+        let action = match terminal.next_command()? {
+            Command::Move(direction) => PlayerAction::Move(direction),
+            Command::Shield => PlayerAction::UseItem(ItemUse::Shield),
+            Command::Aim => match aim(terminal, state, player.pos, shots, &lines, "shoot")? {
+                Some(target) => PlayerAction::Shoot { x: target.x, y: target.y },
+                None => continue,
+            },
+            Command::Dash => match ask_direction(terminal, state, shots, &lines)? {
+                Some(direction) => PlayerAction::UseItem(ItemUse::Dash(direction)),
+                None => continue,
+            },
+            Command::Repair => match aim(terminal, state, player.pos, shots, &lines, "repair")? {
+                Some(target) => PlayerAction::UseItem(ItemUse::RepairKit { x: target.x, y: target.y }),
+                None => continue,
+            },
+            Command::Freeze => match ask_player(terminal, state, shots, &lines)? {
+                Some(player_id) => PlayerAction::UseItem(ItemUse::Freeze { player_id }),
+                None => continue,
+            },
+            Command::Cancel | Command::Quit => return Ok(None),
+            Command::Confirm | Command::Player(_) => continue,
+        };
+        match state.validate_action(player.id, &action) {
+            Ok(()) => return Ok(Some(action)),
+            Err(e) => error = Some(format!("Invalid action: {}", e)),
+        }
+    }
+}
 
-        game_state.next_turn(); // Advance to the next turn.
+/// Let the player move a cursor from `from` to a tile to `verb` (shoot, repair), or `None`
+/// if they cancel.
+fn aim(
+    terminal: &RawTerminal,
+    state: &GameState,
+    from: Position,
+    shots: &[Position],
+    messages: &[String],
+    verb: &str,
+) -> io::Result<Option<Position>> {
+    let rows = state.grid.len();
+    let cols = state.grid.first().map_or(0, |row| row.len());
+    let mut cursor = from;
+    let mut lines = messages.to_vec();
+    lines.push(format!("Aim with the arrows (or WASD/QEZC), Enter to {}, Esc to cancel.", verb));
+    loop {
+        terminal.draw(&render_frame(state, shots, Some(cursor), &lines))?;
+        match terminal.next_command()? {
+            // The cursor moves over rows and columns whatever the board's topology.
+            Command::Move(Direction::Stay) | Command::Aim | Command::Confirm => return Ok(Some(cursor)),
+            Command::Move(direction) => {
+                cursor = Topology::EightWay.neighbour(cursor, direction, rows, cols).unwrap_or(cursor);
+            }
+            Command::Cancel | Command::Quit => return Ok(None),
+            _ => {}
+        }
+    }
+}
 
-        print_player_state(&game_state.players[0]);
-        print_grid(&game_state.grid, &game_state.players, &game_state.cannonballs);
+/// Ask the player for a direction to dash in, or `None` if they cancel.
+fn ask_direction(
+    terminal: &RawTerminal,
+    state: &GameState,
+    shots: &[Position],
+    messages: &[String],
+) -> io::Result<Option<Direction>> {
+    let mut lines = messages.to_vec();
+    lines.push("Dash in which direction? Arrows (or WASD/QEZC), Esc to cancel.".to_string());
+    terminal.draw(&render_frame(state, shots, None, &lines))?;
+    loop {
+        match terminal.next_command()? {
+            Command::Move(Direction::Stay) => {}
+            Command::Move(direction) => return Ok(Some(direction)),
+            Command::Cancel | Command::Quit => return Ok(None),
+            _ => {}
+        }
+    }
+}
 
-        // If the player is dead, end the game.
-        if !game_state.players[0].is_alive {
-            println!("Player {} is dead. Game Over!", game_state.players[0].id);
-            break;
+/// Ask the player for the ID of a player to freeze, or `None` if they cancel.
+fn ask_player(
+    terminal: &RawTerminal,
+    state: &GameState,
+    shots: &[Position],
+    messages: &[String],
+) -> io::Result<Option<u8>> {
+    let mut lines = messages.to_vec();
+    lines.push("Freeze which player? Press their number, Esc to cancel.".to_string());
+    terminal.draw(&render_frame(state, shots, None, &lines))?;
+    loop {
+        match terminal.next_command()? {
+            Command::Player(player_id) => return Ok(Some(player_id)),
+            Command::Cancel | Command::Quit => return Ok(None),
+            _ => {}
         }
     }
-}
\ No newline at end of file
+}
+
+/// Lines describing the events of the last turn.
+fn event_lines(events: &[GameEvent]) -> Vec<String> {
+    if events.is_empty() {
+        return Vec::new();
+    }
+    let mut lines = vec!["Last turn:".to_string()];
+    lines.extend(events.iter().map(|event| format!("  {}", event_text(event))));
+    lines
+}
+
+/// Readable description of an event.
+pub fn event_text(event: &GameEvent) -> String {
+    let tile = |pos: Position| format!("({}, {})", pos.x, pos.y);
+    match *event {
+        GameEvent::PlayerMoved { player_id, to, .. } => format!("P{} moved to {}.", player_id, tile(to)),
+        GameEvent::PlayerTeleported { player_id, to, .. } => format!("P{} teleported to {}.", player_id, tile(to)),
+        GameEvent::PlayerBounced { player_id, pos, .. } => format!("P{} was blocked and stayed on {}.", player_id, tile(pos)),
+        GameEvent::CannonballSpawned { pos } => format!("A cannonball appeared on {}.", tile(pos)),
+        GameEvent::CannonballPickedUp { player_id, pos } => format!("P{} picked up a cannonball on {}.", player_id, tile(pos)),
+        GameEvent::ItemSpawned { pos, item } => format!("A {:?} appeared on {}.", item, tile(pos)),
+        GameEvent::ItemPickedUp { player_id, pos, item } => format!("P{} picked up a {:?} on {}.", player_id, item, tile(pos)),
+        GameEvent::ItemUsed { player_id, item } => format!("P{} used a {:?}.", player_id, item),
+        GameEvent::ItemDestroyed { pos, item } => format!("The {:?} on {} was destroyed.", item, tile(pos)),
+        GameEvent::ShieldAbsorbed { player_id, pos } => format!("P{}'s shield saved them on {}.", player_id, tile(pos)),
+        GameEvent::TileRepaired { pos } => format!("Tile {} was repaired.", tile(pos)),
+        GameEvent::PlayerFrozen { player_id, by } => format!("P{} froze P{}.", by, player_id),
+        GameEvent::ActionSkipped { player_id } => format!("P{} is frozen and skipped their action.", player_id),
+        GameEvent::ShotFired { player_id, target } => format!("P{} shot at {}.", player_id, tile(target)),
+        GameEvent::PlayerKnockedBack { player_id, to, .. } => format!("P{} was knocked back to {}.", player_id, tile(to)),
+        GameEvent::TileCracked { pos } => format!("Tile {} cracked.", tile(pos)),
+        GameEvent::TileBroken { pos } => format!("Tile {} broke.", tile(pos)),
+        GameEvent::CannonballDestroyed { pos } => format!("The cannonball on {} was destroyed.", tile(pos)),
+        GameEvent::PointScored { player_id, score, .. } => format!("P{} held the hill and has {} points.", player_id, score),
+        GameEvent::PlayerEliminated { player_id, pos } => format!("P{} was eliminated on {}.", player_id, tile(pos)),
+    }
+}
+
+/// Tiles shot at during the last turn.
+pub fn shot_tiles(events: &[GameEvent]) -> Vec<Position> {
+    events.iter()
+        .filter_map(|event| match event {
+            GameEvent::ShotFired { target, .. } => Some(*target),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Line announcing the result of the game.
+fn result_line(result: &GameResult) -> String {
+    match result {
+        GameResult::Winner { player_id } => format!("P{} wins!", player_id),
+        GameResult::TeamWinner { team_id, .. } => format!("Team {} wins!", team_id),
+        GameResult::Draw { player_ids } => format!("Draw between {:?}.", player_ids),
+    }
+}
@@ -0,0 +1,86 @@
+//! Key bindings of the terminal client.
+//!
+//! | Keys                    | Command                                         |
+//! |-------------------------|-------------------------------------------------|
+//! | Arrows, `W` `A` `S` `D` | Move up, left, down, right                      |
+//! | `Q` `E` `Z` `C`         | Move up-left, up-right, down-left, down-right   |
+//! | Space, `.`              | Stay in place                                   |
+//! | `F`                     | Aim a shot (move the cursor, then `Enter`)      |
+//! | `H`                     | Arm a shield                                    |
+//! | `G`                     | Dash (then pick a direction)                    |
+//! | `R`                     | Repair a tile (move the cursor, then `Enter`)   |
+//! | `X`                     | Freeze a player (then press their number)       |
+//! | `1` to `9`              | Pick a player                                   |
+//! | `Enter`                 | Confirm                                         |
+//! | `Esc`                   | Cancel the aim, or quit                         |
+//! | `Ctrl+C`                | Quit                                            |
+//!
+//! Diagonals only exist on eight-way and hexagonal boards, and hexagons have no straight
+//! up or down: moves the board lacks are rejected like any other invalid action.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+use crate::game::types::Direction;
+
+/// What a key press asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Move (or move the aiming cursor) in a direction.
+    Move(Direction),
+    /// Start aiming a shot.
+    Aim,
+    /// Arm a shield.
+    Shield,
+    /// Start a dash.
+    Dash,
+    /// Start aiming a repair.
+    Repair,
+    /// Start picking a player to freeze.
+    Freeze,
+    /// Pick the player with this ID.
+    Player(u8),
+    /// Confirm the current choice.
+    Confirm,
+    /// Cancel the current choice.
+    Cancel,
+    /// Leave the game.
+    Quit,
+}
+
+/// Command bound to a key press, or `None` for unbound keys and key releases.
+pub fn command_for(key: KeyEvent) -> Option<Command> {
+    if key.kind != KeyEventKind::Press {
+        return None;
+    }
+    if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+        return Some(Command::Quit);
+    }
+    let command = match key.code {
+        KeyCode::Up => Command::Move(Direction::Up),
+        KeyCode::Down => Command::Move(Direction::Down),
+        KeyCode::Left => Command::Move(Direction::Left),
+        KeyCode::Right => Command::Move(Direction::Right),
+        KeyCode::Enter => Command::Confirm,
+        KeyCode::Esc => Command::Cancel,
+        KeyCode::Char(c) => match c.to_ascii_lowercase() {
+            'w' => Command::Move(Direction::Up),
+            'a' => Command::Move(Direction::Left),
+            's' => Command::Move(Direction::Down),
+            'd' => Command::Move(Direction::Right),
+            'q' => Command::Move(Direction::UpLeft),
+            'e' => Command::Move(Direction::UpRight),
+            'z' => Command::Move(Direction::DownLeft),
+            'c' => Command::Move(Direction::DownRight),
+            ' ' | '.' => Command::Move(Direction::Stay),
+            'f' => Command::Aim,
+            'h' => Command::Shield,
+            'g' => Command::Dash,
+            'r' => Command::Repair,
+            'x' => Command::Freeze,
+            '1'..='9' => Command::Player(c as u8 - b'0'),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(command)
+}
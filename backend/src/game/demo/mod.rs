@@ -1,5 +1,13 @@
-// Demo module for the game. Provides submodules for running demo game loops,
-// rendering demo scenes, and standalone demo execution.
+//! Local terminal client.
+//!
+//! A hot-seat game for two to four players sharing one keyboard, with optional bot
+//! opponents, driven directly by [`GameState::step`](crate::game::state::GameState::step).
+//! The `demo` binary is its entry point.
+//!
+//! - [`input`] maps key presses to commands.
+//! - [`render`] draws the game with ANSI colors.
+//! - [`game_loop`] runs the game in a raw-mode terminal.
+
 pub mod game_loop;
+pub mod input;
 pub mod render;
-pub mod standalone;
\ No newline at end of file
@@ -1,39 +1,114 @@
-//! Game rendering system (terminal).
+//! ANSI rendering of the game for the terminal client.
 //!
-//! This module provides functions to print the grid and player state for debugging/demo.
+//! Each tile is two characters wide, colored by its [`Cell`] state. Players are drawn as
+//! `P` and their ID in their own color, cannonballs as `()`, items as `**`, and tiles
+//! shot at on the last turn get a red background. Rows are indented following the board's
+//! [`Topology`](crate::game::topology::Topology). Frames use `\n` line endings.
 
-use crate::game::types::{Player, Cell, Cannonball};
+use crossterm::style::{Color, Stylize};
 
-/// Print the grid, players, and cannonballs to the terminal.
-pub fn print_grid(grid: &Vec<Vec<Cell>>, players: &Vec<Player>, cannonballs: &Vec<Cannonball>) {
-    for (y, row) in grid.iter().enumerate() {
-        for (x, cell) in row.iter().enumerate() {
-            // Default symbol for the cell.
-            let mut symbol = match cell {
-                Cell::Solid => "██".to_string(),
-                Cell::Cracked => "Ck".to_string(),
-                Cell::Broken => "  ".to_string(),
-            };
+use crate::game::types::{Cell, Position};
+use crate::game::state::GameState;
+
+/// Color of each player, by player ID (from 1).
+const PLAYER_COLORS: [Color; 4] = [Color::Cyan, Color::Magenta, Color::Green, Color::Yellow];
+
+/// Color of the player with the given ID.
+pub fn player_color(player_id: u8) -> Color {
+    PLAYER_COLORS[(player_id as usize + PLAYER_COLORS.len() - 1) % PLAYER_COLORS.len()]
+}
 
-            // If a player is alive on this tile, display the player.
-            if let Some(player) = players.iter().find(|p| p.pos.x == x && p.pos.y == y && p.is_alive) {
-                symbol = format!("P{}", player.id);
+/// Background color and filler text of a cell.
+fn cell_style(cell: Cell) -> (Color, String) {
+    match cell {
+        Cell::Solid => (Color::DarkGrey, "  ".to_string()),
+        Cell::Cracked => (Color::DarkYellow, "::".to_string()),
+        Cell::Broken => (Color::DarkRed, "~~".to_string()),
+        Cell::Ice => (Color::DarkCyan, "  ".to_string()),
+        Cell::Wall => (Color::Grey, "##".to_string()),
+        Cell::Teleporter { channel } => (Color::DarkMagenta, format!("{:>2}", channel)),
+        Cell::Hill => (Color::DarkGreen, "/\\".to_string()),
+        Cell::SpawnPad => (Color::DarkBlue, "  ".to_string()),
+    }
+}
+
+/// Draw the grid, highlighting the `shots` of the last turn and `cursor` (the tile being
+/// aimed at) if any.
+pub fn render_grid(state: &GameState, shots: &[Position], cursor: Option<Position>) -> String {
+    let topology = state.rules.topology;
+    let mut out = String::new();
+    for (y, row) in state.grid.iter().enumerate() {
+        out.push_str(&" ".repeat(topology.row_indent(y)));
+        for (x, cell) in row.iter().enumerate() {
+            let pos = Position { x, y };
+            let (mut background, filler) = cell_style(*cell);
+            if shots.contains(&pos) {
+                background = Color::Red;
             }
-            // If no player, but a cannonball is present, display the cannonball.
-            else if let Some(_) = cannonballs.iter().find(|c| c.pos.x == x && c.pos.y == y) {
-                symbol = "CB".to_string();
+
+            let tile = if let Some(player) = state.players.iter().find(|p| p.is_alive && p.pos == pos) {
+                format!("P{}", player.id).with(player_color(player.id)).bold()
+            } else if state.cannonballs.iter().any(|c| c.pos == pos) {
+                "()".to_string().with(Color::White).bold()
+            } else if state.items.iter().any(|i| i.pos == pos) {
+                "**".to_string().with(Color::Yellow)
+            } else {
+                filler.with(Color::Black)
+            };
+            let tile = tile.on(background);
+            if cursor == Some(pos) {
+                out.push_str(&tile.reverse().to_string());
+            } else {
+                out.push_str(&tile.to_string());
             }
+        }
+        out.push('\n');
+    }
+    out
+}
 
-            print!("{:<3}", symbol);
+/// One status line per player: ID, name, team, ammunition, items and status.
+pub fn render_players(state: &GameState) -> String {
+    let mut out = String::new();
+    for player in &state.players {
+        let mut line = format!("P{} {:<12}", player.id, player.username);
+        if let Some(team) = player.team_id {
+            line.push_str(&format!(" team {}", team));
+        }
+        line.push_str(&format!(" ammo {}", player.cannonball_count));
+        if player.score > 0 {
+            line.push_str(&format!(" score {}", player.score));
         }
-        println!("\n");
+        if !player.inventory.is_empty() {
+            line.push_str(&format!(" items {:?}", player.inventory));
+        }
+        if player.shielded {
+            line.push_str(" [shielded]");
+        }
+        if player.frozen {
+            line.push_str(" [frozen]");
+        }
+        let line = if player.is_alive {
+            line.with(player_color(player.id))
+        } else {
+            format!("{} (eliminated)", line).with(Color::DarkGrey)
+        };
+        out.push_str(&line.to_string());
+        out.push('\n');
     }
+    out
 }
 
-/// Print the state of a single player.
-pub fn print_player_state(player: &Player) {
-    println!("--- Player {} ---", player.id);
-    println!("Position: ({}, {})", player.pos.x, player.pos.y);
-    println!("Cannonballs: {}", player.cannonball_count);
-    println!();
-}
\ No newline at end of file
+/// Full frame: title, grid, player statuses, then the given message lines.
+pub fn render_frame(state: &GameState, shots: &[Position], cursor: Option<Position>, messages: &[String]) -> String {
+    let mut out = format!("Lava Grid - {:?} - turn {}\n\n", state.mode, state.turn);
+    out.push_str(&render_grid(state, shots, cursor));
+    out.push('\n');
+    out.push_str(&render_players(state));
+    for message in messages {
+        out.push('\n');
+        out.push_str(message);
+    }
+    out.push('\n');
+    out
+}
@@ -1,7 +0,0 @@
-//! Standalone entry point for running the game loop locally (for testing/demo).
-
-use crate::game::demo::game_loop::run_game_loop;
-
-fn main() {
-    run_game_loop();
-}
\ No newline at end of file
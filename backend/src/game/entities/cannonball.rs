@@ -1,25 +1,38 @@
 //! Cannonball entity logic.
 //!
-//! This module handles spawning cannonballs and shooting them.
+//! This module handles spawning cannonballs (at game start and periodically, following
+//! the mode's [`SpawnSchedule`]) and shooting them, within the range and
+//! line-of-sight limits of the game rules.
 
-use crate::game::types::{Player, Cell, Position, Cannonball, TargetedTile};
+use crate::game::types::{Player, Cell, Position, Cannonball, TargetedTile, Item};
+use crate::game::topology::Topology;
 use crate::game::state::GameState;
+use crate::game::events::GameEvent;
+use crate::game::rng::GameRng;
+use crate::game::errors::ActionError;
+use crate::game::modes::mode_rules;
+use log::warn;
 use rand::seq::IteratorRandom;
 
 /// Spawn random cannonballs on valid grid positions.
-/// Ensures cannonballs do not overlap with players or other cannonballs.
+/// Ensures cannonballs do not overlap with players, items or other cannonballs, and lie at
+/// least `min_distance` moves (on the given topology) away from every living player.
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_random_cannonballs(
-    grid: &Vec<Vec<Cell>>,
-    players: &Vec<Player>,
-    cannonballs: &Vec<Cannonball>,
+    grid: &[Vec<Cell>],
+    players: &[Player],
+    cannonballs: &[Cannonball],
+    items: &[Item],
     count: usize,
+    topology: Topology,
+    min_distance: usize,
+    rng: &mut GameRng,
 ) -> Vec<Cannonball> {
-    let mut rng = rand::rng();
 
-    // Collect all solid tiles.
+    // Collect all solid and ice tiles.
     let valid_positions: Vec<Position> = grid.iter().enumerate()
-        .flat_map(|(x, row)| row.iter().enumerate().filter_map(move |(y, cell)| {
-            if *cell == Cell::Solid {
+        .flat_map(|(y, row)| row.iter().enumerate().filter_map(move |(x, cell)| {
+            if matches!(cell, Cell::Solid | Cell::Ice) {
                 Some(Position { x, y })
             } else {
                 None
@@ -30,36 +43,140 @@ pub fn spawn_random_cannonballs(
     // Mark positions occupied by players and existing cannonballs.
     let occupied_positions: Vec<Position> = players.iter().map(|p| p.pos)
         .chain(cannonballs.iter().map(|c| c.pos))
+        .chain(items.iter().map(|i| i.pos))
         .collect();
 
-    // Only keep positions not occupied.
+    // Only keep positions not occupied and far enough from living players.
+    let far_enough = |pos: &Position| players.iter()
+        .filter(|p| p.is_alive)
+        .all(|p| topology.distance(p.pos, *pos) >= min_distance);
     let free_positions: Vec<Position> = valid_positions
         .into_iter()
-        .filter(|pos| !occupied_positions.contains(pos))
+        .filter(|pos| !occupied_positions.contains(pos) && far_enough(pos))
         .collect();
 
-    if free_positions.len() == 0 {
-        println!("[WARN] No free tile to place a cannonball!");
+    if free_positions.is_empty() {
+        warn!("No free tile to place a cannonball!");
         return vec![];
     }
 
     // Randomly select positions for new cannonballs.
     free_positions.iter()
-        .choose_multiple(&mut rng, count.min(free_positions.len()))
+        .choose_multiple(rng, count.min(free_positions.len()))
         .into_iter()
         .map(|pos| Cannonball { pos: *pos })
         .collect()
 }
 
+/// Spawn new cannonballs if the mode's respawn schedule calls for it this turn.
+pub fn respawn_cannonballs(game_state: &mut GameState, events: &mut Vec<GameEvent>) {
+    let count = mode_rules(game_state.mode).cannonball_schedule(&game_state.rules)
+        .spawn_count(game_state.turn, game_state.cannonballs.len());
+    if count == 0 {
+        return;
+    }
+
+    let spawned = spawn_random_cannonballs(
+        &game_state.grid,
+        &game_state.players,
+        &game_state.cannonballs,
+        &game_state.items,
+        count,
+        game_state.rules.topology,
+        game_state.rules.cannonball_min_distance,
+        &mut game_state.rng,
+    );
+    for cannonball in spawned {
+        events.push(GameEvent::CannonballSpawned { pos: cannonball.pos });
+        game_state.cannonballs.push(cannonball);
+    }
+}
+
+/// Check that the player at `player_index` may shoot at `target`, ignoring ammunition.
+///
+/// The target must be a tile of the grid that is neither broken nor a wall, within the
+/// rules' shot range of the player, and no wall may stand on a tile strictly between the
+/// shooter and the target (see [`Topology::line_between`](crate::game::topology::Topology::line_between)). When line of sight is required, other living players block the
+/// shot too.
+pub fn check_target(game_state: &GameState, player_index: usize, target: Position) -> Result<(), ActionError> {
+    let rows = game_state.grid.len();
+    let cols = game_state.grid.first().map_or(0, |row| row.len());
+    if target.x >= cols || target.y >= rows {
+        return Err(ActionError::OutOfBounds);
+    }
+    match game_state.grid[target.y][target.x] {
+        Cell::Broken => return Err(ActionError::TargetBroken),
+        Cell::Wall => return Err(ActionError::Blocked),
+        _ => {}
+    }
+
+    let shooter = &game_state.players[player_index];
+    let topology = game_state.rules.topology;
+    if let Some(range) = game_state.rules.shot_range
+        && range.distance(topology, shooter.pos, target) > range.max
+    {
+        return Err(ActionError::OutOfRange);
+    }
+
+    let line = topology.line_between(shooter.pos, target, rows, cols);
+    if line.iter().any(|pos| game_state.grid[pos.y][pos.x] == Cell::Wall) {
+        return Err(ActionError::Blocked);
+    }
+    if game_state.rules.shot_line_of_sight {
+        let blocked = line.iter().any(|pos| {
+            game_state.players.iter().any(|p| p.is_alive && p.id != shooter.id && p.pos == *pos)
+        });
+        if blocked {
+            return Err(ActionError::NoLineOfSight);
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether `target` is a legal target for the player at `player_index`, ignoring ammunition.
+pub fn is_legal_target(game_state: &GameState, player_index: usize, target: Position) -> bool {
+    check_target(game_state, player_index, target).is_ok()
+}
+
+/// All tiles the player at `player_index` can shoot at right now.
+///
+/// Empty if the player is eliminated or has no cannonball.
+pub fn legal_targets(game_state: &GameState, player_index: usize) -> Vec<Position> {
+    let player = &game_state.players[player_index];
+    if !player.is_alive || player.cannonball_count == 0 {
+        return Vec::new();
+    }
+    game_state.grid.iter().enumerate()
+        .flat_map(|(y, row)| (0..row.len()).map(move |x| Position { x, y }))
+        .filter(|&pos| is_legal_target(game_state, player_index, pos))
+        .collect()
+}
+
 /// Attempt to shoot a cannonball at the specified tile for the given player.
-/// Only succeeds if the player has at least one cannonball and the tile is not already targeted.
-pub fn shoot_cannonball(game_state: &mut GameState, player_id: usize, x: usize, y: usize) {
+/// Only succeeds if the player has at least one cannonball and the tile is a legal
+/// target (see [`is_legal_target`]).
+///
+/// Shots of a turn are simultaneous: when several players fire at the same tile from the
+/// same position, each of them spends a cannonball but the tile is only targeted once.
+pub fn shoot_cannonball(
+    game_state: &mut GameState,
+    player_id: usize,
+    x: usize,
+    y: usize,
+    events: &mut Vec<GameEvent>,
+) {
+    if !is_legal_target(game_state, player_id, Position { x, y }) {
+        return;
+    }
     let player = &mut game_state.players[player_id];
-    if player.cannonball_count > 0 {
-        // Only allow shooting if the tile is not already targeted this turn.
-        if !game_state.targeted_tiles.iter().any(|t| t.x == x && t.y == y) {
-            game_state.targeted_tiles.push(TargetedTile { x, y });
-            player.cannonball_count -= 1;
-        }
+    if player.cannonball_count == 0 {
+        return;
+    }
+    player.cannonball_count -= 1;
+    events.push(GameEvent::ShotFired { player_id: player.id, target: Position { x, y } });
+    let tile = TargetedTile { x, y, origin: player.pos, team_id: player.team_id };
+    if !game_state.targeted_tiles.contains(&tile) {
+        game_state.targeted_tiles.push(tile);
     }
 }
\ No newline at end of file
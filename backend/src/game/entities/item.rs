@@ -0,0 +1,123 @@
+//! Item entity logic.
+//!
+//! This module handles spawning power-up items from the mode's item pool, picking them up
+//! and using them from a player's inventory.
+
+use rand::seq::IndexedRandom;
+
+use crate::game::types::{Cell, Position, Item, ItemUse};
+use crate::game::state::GameState;
+use crate::game::events::GameEvent;
+use crate::game::errors::ActionError;
+use crate::game::modes::mode_rules;
+
+/// Spawn new items if the item schedule calls for it this turn.
+///
+/// Items are drawn uniformly from the mode's item pool; modes with an empty pool never
+/// spawn items.
+pub fn respawn_items(game_state: &mut GameState, events: &mut Vec<GameEvent>) {
+    let pool = mode_rules(game_state.mode).item_pool(&game_state.rules).to_vec();
+    if pool.is_empty() {
+        return;
+    }
+    let count = game_state.rules.item_schedule.spawn_count(game_state.turn, game_state.items.len());
+    if count == 0 {
+        return;
+    }
+
+    for pos in free_item_spots(game_state, count) {
+        let kind = *pool.choose(&mut game_state.rng).expect("item pool is not empty");
+        events.push(GameEvent::ItemSpawned { pos, item: kind });
+        game_state.items.push(Item { pos, kind });
+    }
+}
+
+/// Up to `count` random tiles where an item may spawn: intact or unbreakable tiles that
+/// are not teleporters, holding no player, cannonball or item. Unlike cannonballs, items
+/// may spawn right next to a player.
+fn free_item_spots(game_state: &mut GameState, count: usize) -> Vec<Position> {
+    let occupied: Vec<Position> = game_state.players.iter().map(|p| p.pos)
+        .chain(game_state.cannonballs.iter().map(|c| c.pos))
+        .chain(game_state.items.iter().map(|i| i.pos))
+        .collect();
+    let free: Vec<Position> = game_state.grid.iter().enumerate()
+        .flat_map(|(y, row)| row.iter().enumerate().filter_map(move |(x, cell)| {
+            matches!(cell, Cell::Solid | Cell::Ice | Cell::Hill | Cell::SpawnPad).then_some(Position { x, y })
+        }))
+        .filter(|pos| !occupied.contains(pos))
+        .collect();
+    free.choose_multiple(&mut game_state.rng, count).copied().collect()
+}
+
+/// Pick up the item lying under the player at `player_index`, if any.
+pub fn pick_up_item(game_state: &mut GameState, player_index: usize, events: &mut Vec<GameEvent>) {
+    let player = &mut game_state.players[player_index];
+    if let Some(index) = game_state.items.iter().position(|i| i.pos == player.pos) {
+        let item = game_state.items.remove(index);
+        player.inventory.push(item.kind);
+        events.push(GameEvent::ItemPickedUp { player_id: player.id, pos: item.pos, item: item.kind });
+    }
+}
+
+/// Check that the player at `player_index` may use an item this way.
+///
+/// The player must hold the item. A repair kit needs a broken tile at most one tile away,
+/// and a freeze needs another living player. Dash moves are checked like regular moves.
+pub fn check_item_use(game_state: &GameState, player_index: usize, item_use: ItemUse) -> Result<(), ActionError> {
+    let player = &game_state.players[player_index];
+    if !player.inventory.contains(&item_use.kind()) {
+        return Err(ActionError::NoItem);
+    }
+
+    match item_use {
+        ItemUse::Shield | ItemUse::Dash(_) => Ok(()),
+        ItemUse::RepairKit { x, y } => {
+            let cols = game_state.grid.first().map_or(0, |row| row.len());
+            if y >= game_state.grid.len() || x >= cols {
+                return Err(ActionError::OutOfBounds);
+            }
+            if game_state.grid[y][x] != Cell::Broken {
+                return Err(ActionError::TargetNotBroken);
+            }
+            if game_state.rules.topology.distance(player.pos, Position { x, y }) > 1 {
+                return Err(ActionError::OutOfRange);
+            }
+            Ok(())
+        }
+        ItemUse::Freeze { player_id } => {
+            let valid = player_id != player.id
+                && game_state.players.iter().any(|p| p.id == player_id && p.is_alive);
+            if valid { Ok(()) } else { Err(ActionError::InvalidTarget) }
+        }
+    }
+}
+
+/// Use an item from the inventory of the player at `player_index`.
+///
+/// The item is consumed and its effect applied, except for the dash whose move is resolved
+/// with the other moves of the turn.
+pub fn use_item(game_state: &mut GameState, player_index: usize, item_use: ItemUse, events: &mut Vec<GameEvent>) {
+    let kind = item_use.kind();
+    let player = &mut game_state.players[player_index];
+    let Some(slot) = player.inventory.iter().position(|k| *k == kind) else {
+        return;
+    };
+    player.inventory.remove(slot);
+    let player_id = player.id;
+    events.push(GameEvent::ItemUsed { player_id, item: kind });
+
+    match item_use {
+        ItemUse::Shield => game_state.players[player_index].shielded = true,
+        ItemUse::Dash(_) => {}
+        ItemUse::RepairKit { x, y } => {
+            game_state.grid[y][x] = Cell::Solid;
+            events.push(GameEvent::TileRepaired { pos: Position { x, y } });
+        }
+        ItemUse::Freeze { player_id: target } => {
+            if let Some(frozen) = game_state.players.iter_mut().find(|p| p.id == target) {
+                frozen.frozen = true;
+                events.push(GameEvent::PlayerFrozen { player_id: target, by: player_id });
+            }
+        }
+    }
+}
@@ -1,9 +1,11 @@
 //! Game entities module.
 //!
-//! This module organizes player and cannonball entity logic.
+//! This module organizes player, cannonball and item entity logic.
 
 pub mod player;
 pub mod cannonball;
+pub mod item;
 
 pub use player::*;
-pub use cannonball::*;
\ No newline at end of file
+pub use cannonball::*;
+pub use item::*;
\ No newline at end of file
@@ -1,40 +1,119 @@
 //! Player entity logic.
 //!
-//! This module handles spawning players at random valid positions.
+//! This module handles spawning players following the mode's [`SpawnStrategy`], on spawn
+//! pads when the grid has free ones.
 
-use crate::game::types::{Player, Cell, Position};
-use rand::seq::IteratorRandom;
+use crate::game::types::{Player, Cell, Position, SpawnStrategy};
+use crate::game::topology::Topology;
+use crate::game::rng::GameRng;
+use log::warn;
+use rand::seq::{IndexedRandom, IteratorRandom};
+
+/// Free tiles a player may spawn on: free spawn pads if the grid has any, free solid tiles
+/// otherwise.
+pub fn spawn_candidates(grid: &[Vec<Cell>], players: &[Player]) -> Vec<Position> {
+    // Collect all tiles of the given kind not already occupied by another player.
+    let free_tiles = |kind: Cell| -> Vec<Position> {
+        grid.iter().enumerate()
+            .flat_map(|(y, row)| {
+                row.iter().enumerate().filter_map(move |(x, cell)| {
+                    if *cell == kind && !players.iter().any(|p| p.pos.x == x && p.pos.y == y) {
+                        Some(Position { x, y })
+                    } else {
+                        None
+                    }
+                })
+            })
+            .collect()
+    };
+    let valid_positions = free_tiles(Cell::SpawnPad);
+    if valid_positions.is_empty() {
+        free_tiles(Cell::Solid)
+    } else {
+        valid_positions
+    }
+}
 
 /// Generate a new player at a random valid position on the grid.
+///
+/// Free spawn pads are used first; without any, the player spawns on a free solid tile.
 /// Returns None if no valid position is available.
 pub fn spawn_random_player(
-    grid: &Vec<Vec<Cell>>,
-    players: &Vec<Player>,
+    grid: &[Vec<Cell>],
+    players: &[Player],
     id: u8,
     username: String,
+    rng: &mut GameRng,
 ) -> Option<Player> {
-    let mut rng = rand::rng();
-
-    // Collect all solid tiles not already occupied by another player.
-    let valid_positions: Vec<Position> = grid.iter().enumerate()
-        .flat_map(|(x, row)| {
-            row.iter().enumerate().filter_map(move |(y, cell)| {
-                if *cell == Cell::Solid && !players.iter().any(|p| p.pos.x == x && p.pos.y == y) {
-                    Some(Position { x, y })
-                } else {
-                    None
-                }
-            })
-        })
-        .collect();
-
-    if valid_positions.len() == 0 {
-        println!("[WARN] Cannot place player {}: no free tile.", id);
+    let valid_positions = spawn_candidates(grid, players);
+    if valid_positions.is_empty() {
+        warn!("Cannot place player {}: no free tile.", id);
         return None;
     }
 
     // Randomly select a valid position.
     valid_positions.into_iter()
-        .choose(&mut rng)
+        .choose(rng)
         .map(|pos| Player::new(id, pos, username))
-}
\ No newline at end of file
+}
+
+/// Generate a new player placed with the given strategy, after the players already placed.
+///
+/// `pick` is the tile the player picked for [`SpawnStrategy::Draft`]; it is only used if it
+/// is a free spawn candidate. Distances are counted in moves on the given topology. Returns
+/// None if no valid position is available.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_player(
+    grid: &[Vec<Cell>],
+    topology: Topology,
+    players: &[Player],
+    id: u8,
+    username: String,
+    strategy: SpawnStrategy,
+    pick: Option<Position>,
+    rng: &mut GameRng,
+) -> Option<Player> {
+    let candidates = spawn_candidates(grid, players);
+    let pos = match strategy {
+        SpawnStrategy::Random => return spawn_random_player(grid, players, id, username, rng),
+        SpawnStrategy::Draft => pick
+            .filter(|pick| candidates.contains(pick))
+            .or_else(|| farthest_position(topology, &candidates, players, rng)),
+        SpawnStrategy::Corners => corner_position(grid, topology, &candidates, players.len())
+            .or_else(|| farthest_position(topology, &candidates, players, rng)),
+        SpawnStrategy::MaxMin => farthest_position(topology, &candidates, players, rng),
+    };
+    match pos {
+        Some(pos) => Some(Player::new(id, pos, username)),
+        None => {
+            warn!("Cannot place player {}: no free tile.", id);
+            None
+        }
+    }
+}
+
+/// Candidate farthest from the nearest player already placed, at random among ties.
+fn farthest_position(topology: Topology, candidates: &[Position], players: &[Player], rng: &mut GameRng) -> Option<Position> {
+    let nearest = |pos: Position| {
+        players.iter().map(|p| topology.distance(p.pos, pos)).min().unwrap_or(0)
+    };
+    let best = candidates.iter().map(|&pos| nearest(pos)).max()?;
+    let farthest: Vec<Position> = candidates.iter().copied().filter(|&pos| nearest(pos) == best).collect();
+    farthest.choose(rng).copied()
+}
+
+/// Candidate closest to the `index`-th corner (top-left, bottom-right, top-right,
+/// bottom-left), or `None` beyond four players.
+fn corner_position(grid: &[Vec<Cell>], topology: Topology, candidates: &[Position], index: usize) -> Option<Position> {
+    let rows = grid.len();
+    let cols = grid.first().map_or(0, |row| row.len());
+    let (right, bottom) = (cols.saturating_sub(1), rows.saturating_sub(1));
+    let corner = match index {
+        0 => Position { x: 0, y: 0 },
+        1 => Position { x: right, y: bottom },
+        2 => Position { x: right, y: 0 },
+        3 => Position { x: 0, y: bottom },
+        _ => return None,
+    };
+    candidates.iter().copied().min_by_key(|&pos| topology.distance(pos, corner))
+}
@@ -0,0 +1,146 @@
+//! Errors returned when validating player actions, loading maps and verifying replays.
+//!
+//! [`GameState::validate_action`](crate::game::state::GameState::validate_action) checks an
+//! action against the current state before it is applied, so invalid actions can be
+//! rejected with a precise reason instead of being silently ignored.
+//! [`GameMap::parse`](crate::game::grid::GameMap::parse) reports malformed map files with a
+//! [`MapError`], and [`Replay::verify`](crate::game::replay::Replay::verify) reports
+//! replays that no longer play out as recorded with a [`ReplayError`].
+
+use std::fmt;
+
+/// Reason why a player action is not valid in the current state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionError {
+    /// No player with this ID is part of the game.
+    UnknownPlayer,
+    /// The player has been eliminated and cannot act anymore.
+    PlayerEliminated,
+    /// The current game mode does not allow this kind of action.
+    NotAllowedInMode,
+    /// The move direction does not exist on the board's topology.
+    InvalidDirection,
+    /// The move or shot leads outside the grid.
+    OutOfBounds,
+    /// A wall is in the way of the move or shot.
+    Blocked,
+    /// The player has no cannonball to shoot.
+    NoAmmo,
+    /// The tile is already targeted by a shot awaiting resolution.
+    AlreadyTargeted,
+    /// The tile is already broken.
+    TargetBroken,
+    /// The player does not hold the item they tried to use.
+    NoItem,
+    /// The tile to repair is not broken.
+    TargetNotBroken,
+    /// The targeted player does not exist, is eliminated, or is the player themselves.
+    InvalidTarget,
+    /// The tile is farther than the allowed shot range.
+    OutOfRange,
+    /// Another player stands between the shooter and the tile.
+    NoLineOfSight,
+}
+
+impl ActionError {
+    /// Stable error code sent to clients.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ActionError::UnknownPlayer => "UNKNOWN_PLAYER",
+            ActionError::PlayerEliminated => "PLAYER_ELIMINATED",
+            ActionError::NotAllowedInMode => "ACTION_NOT_ALLOWED",
+            ActionError::InvalidDirection => "INVALID_DIRECTION",
+            ActionError::OutOfBounds => "OUT_OF_BOUNDS",
+            ActionError::Blocked => "BLOCKED",
+            ActionError::NoAmmo => "NO_AMMO",
+            ActionError::AlreadyTargeted => "ALREADY_TARGETED",
+            ActionError::TargetBroken => "TARGET_BROKEN",
+            ActionError::NoItem => "NO_ITEM",
+            ActionError::TargetNotBroken => "TARGET_NOT_BROKEN",
+            ActionError::InvalidTarget => "INVALID_TARGET",
+            ActionError::OutOfRange => "OUT_OF_RANGE",
+            ActionError::NoLineOfSight => "NO_LINE_OF_SIGHT",
+        }
+    }
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            ActionError::UnknownPlayer => "Unknown player: you are not part of this game.",
+            ActionError::PlayerEliminated => "You are eliminated and cannot act anymore.",
+            ActionError::NotAllowedInMode => "This action is not allowed in this game mode.",
+            ActionError::InvalidDirection => "This direction does not exist on this board.",
+            ActionError::OutOfBounds => "This action leads outside the grid.",
+            ActionError::Blocked => "A wall is in the way.",
+            ActionError::NoAmmo => "You have no cannonball to shoot.",
+            ActionError::AlreadyTargeted => "This tile is already targeted.",
+            ActionError::TargetBroken => "This tile is already broken.",
+            ActionError::NoItem => "You do not hold this item.",
+            ActionError::TargetNotBroken => "This tile is not broken.",
+            ActionError::InvalidTarget => "This player cannot be targeted.",
+            ActionError::OutOfRange => "This tile is out of your shot range.",
+            ActionError::NoLineOfSight => "Another player blocks your line of sight to this tile.",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for ActionError {}
+
+/// Reason why a map file could not be loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapError {
+    /// The file does not start with a `name:` line.
+    MissingName,
+    /// The map has no tiles.
+    Empty,
+    /// A character of the grid is not part of the map legend.
+    UnknownTile { row: usize, col: usize, tile: char },
+    /// A teleporter channel does not have exactly two tiles.
+    UnpairedTeleporter { channel: u8 },
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapError::MissingName => f.write_str("The map does not start with a `name:` line."),
+            MapError::Empty => f.write_str("The map has no tiles."),
+            MapError::UnknownTile { row, col, tile } => {
+                write!(f, "Unknown tile '{}' at row {}, column {}.", tile, row, col)
+            }
+            MapError::UnpairedTeleporter { channel } => {
+                write!(f, "Teleporter channel {} does not have exactly two tiles.", channel)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MapError {}
+
+/// Reason why a replay could not be played again as recorded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The replay names a map that does not exist (anymore).
+    UnknownMap { name: String },
+    /// The state after a turn (0 for the initial state) differs from the recorded one.
+    StateMismatch { turn: u32, expected: u64, actual: u64 },
+    /// The game ends differently than recorded.
+    OutcomeMismatch,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::UnknownMap { name } => write!(f, "Unknown map '{}'.", name),
+            ReplayError::StateMismatch { turn, expected, actual } => write!(
+                f,
+                "State after turn {} differs from the recording (hash {:016x}, expected {:016x}).",
+                turn, actual, expected,
+            ),
+            ReplayError::OutcomeMismatch => f.write_str("The game ends differently than recorded."),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
@@ -0,0 +1,128 @@
+//! Typed log of what happened during a turn.
+//!
+//! The game core records a [`GameEvent`] for every observable change it makes while
+//! resolving a turn, so clients can animate the turn instead of diffing grids.
+
+use serde::{Serialize, Deserialize};
+
+use crate::game::types::{Position, ItemKind};
+
+/// Something that happened while resolving a turn, in the order it happened.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", content = "data")]
+pub enum GameEvent {
+    /// A player moved from one tile to another (`from` is `None` when hidden by the fog).
+    PlayerMoved { player_id: u8, from: Option<Position>, to: Position },
+    /// A player ending their move on a teleporter was sent to its paired teleporter
+    /// (`from` is `None` when hidden by the fog).
+    PlayerTeleported { player_id: u8, from: Option<Position>, to: Position },
+    /// A player's move was blocked by another player and they stayed on `pos`.
+    PlayerBounced { player_id: u8, pos: Position, toward: Position },
+    /// A new cannonball appeared on the grid.
+    CannonballSpawned { pos: Position },
+    /// A player picked up a cannonball lying on their tile.
+    CannonballPickedUp { player_id: u8, pos: Position },
+    /// A new item appeared on the grid.
+    ItemSpawned { pos: Position, item: ItemKind },
+    /// A player picked up an item lying on their tile.
+    ItemPickedUp { player_id: u8, pos: Position, item: ItemKind },
+    /// A player used an item from their inventory.
+    ItemUsed { player_id: u8, item: ItemKind },
+    /// An item lying on the grid was destroyed with its tile.
+    ItemDestroyed { pos: Position, item: ItemKind },
+    /// A player's shield absorbed their fall; the tile under them is solid again.
+    ShieldAbsorbed { player_id: u8, pos: Position },
+    /// A broken tile was repaired and is solid again.
+    TileRepaired { pos: Position },
+    /// A player was frozen and will skip their next action.
+    PlayerFrozen { player_id: u8, by: u8 },
+    /// A frozen player's action was skipped this turn.
+    ActionSkipped { player_id: u8 },
+    /// A player fired a cannonball at a tile (resolved at the end of the turn).
+    ShotFired { player_id: u8, target: Position },
+    /// A player standing in a blast was pushed to an adjacent tile (`from` is `None` when
+    /// hidden by the fog).
+    PlayerKnockedBack { player_id: u8, from: Option<Position>, to: Position },
+    /// A solid tile cracked and will break soon.
+    TileCracked { pos: Position },
+    /// A tile broke and became lethal.
+    TileBroken { pos: Position },
+    /// A cannonball lying on the grid was destroyed with its tile.
+    CannonballDestroyed { pos: Position },
+    /// A player held the hill alone and scored a point.
+    PointScored { player_id: u8, pos: Position, score: u32 },
+    /// A player was eliminated (fell on a broken tile or left the grid).
+    PlayerEliminated { player_id: u8, pos: Position },
+}
+
+impl GameEvent {
+    /// Tile where the event can be seen (the landing tile for moves), if any.
+    pub fn location(&self) -> Option<Position> {
+        match *self {
+            GameEvent::PlayerMoved { to, .. }
+            | GameEvent::PlayerTeleported { to, .. }
+            | GameEvent::PlayerKnockedBack { to, .. } => Some(to),
+            GameEvent::ShotFired { target, .. } => Some(target),
+            GameEvent::PlayerBounced { pos, .. }
+            | GameEvent::CannonballSpawned { pos }
+            | GameEvent::CannonballPickedUp { pos, .. }
+            | GameEvent::ItemSpawned { pos, .. }
+            | GameEvent::ItemPickedUp { pos, .. }
+            | GameEvent::ItemDestroyed { pos, .. }
+            | GameEvent::ShieldAbsorbed { pos, .. }
+            | GameEvent::TileRepaired { pos }
+            | GameEvent::TileCracked { pos }
+            | GameEvent::TileBroken { pos }
+            | GameEvent::CannonballDestroyed { pos }
+            | GameEvent::PointScored { pos, .. }
+            | GameEvent::PlayerEliminated { pos, .. } => Some(pos),
+            GameEvent::ItemUsed { .. } | GameEvent::PlayerFrozen { .. } | GameEvent::ActionSkipped { .. } => None,
+        }
+    }
+
+    /// Tile a player left, for moves.
+    pub fn origin(&self) -> Option<Position> {
+        match *self {
+            GameEvent::PlayerMoved { from, .. }
+            | GameEvent::PlayerTeleported { from, .. }
+            | GameEvent::PlayerKnockedBack { from, .. } => from,
+            _ => None,
+        }
+    }
+
+    /// Forget the tile a player left, for moves.
+    pub fn hide_origin(&mut self) {
+        match self {
+            GameEvent::PlayerMoved { from, .. }
+            | GameEvent::PlayerTeleported { from, .. }
+            | GameEvent::PlayerKnockedBack { from, .. } => *from = None,
+            _ => {}
+        }
+    }
+
+    /// Players the event is about.
+    pub fn players(&self) -> Vec<u8> {
+        match *self {
+            GameEvent::PlayerFrozen { player_id, by } => vec![player_id, by],
+            GameEvent::PlayerMoved { player_id, .. }
+            | GameEvent::PlayerTeleported { player_id, .. }
+            | GameEvent::PlayerBounced { player_id, .. }
+            | GameEvent::CannonballPickedUp { player_id, .. }
+            | GameEvent::ItemPickedUp { player_id, .. }
+            | GameEvent::ItemUsed { player_id, .. }
+            | GameEvent::ShieldAbsorbed { player_id, .. }
+            | GameEvent::ActionSkipped { player_id }
+            | GameEvent::ShotFired { player_id, .. }
+            | GameEvent::PlayerKnockedBack { player_id, .. }
+            | GameEvent::PointScored { player_id, .. }
+            | GameEvent::PlayerEliminated { player_id, .. } => vec![player_id],
+            GameEvent::CannonballSpawned { .. }
+            | GameEvent::ItemSpawned { .. }
+            | GameEvent::ItemDestroyed { .. }
+            | GameEvent::TileRepaired { .. }
+            | GameEvent::TileCracked { .. }
+            | GameEvent::TileBroken { .. }
+            | GameEvent::CannonballDestroyed { .. } => Vec::new(),
+        }
+    }
+}
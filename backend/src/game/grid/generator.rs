@@ -0,0 +1,157 @@
+//! Seeded procedural map generator.
+//!
+//! Generated maps are symmetric: obstacles (holes and walls), spawn pads and cannonballs
+//! are placed in one part of the grid and mirrored into the others. Spawn pads sit in the
+//! four corners. Square boards are mirrored along both axes, so every spawn sees the same
+//! map around it. Hexagonal boards cannot be mirrored left to right (odd rows are shifted),
+//! so they only get the symmetry their rows allow, and the generator checks that every
+//! spawn is at the same walking distance from the nearest cannonball rather than relying
+//! on symmetry. Layouts whose walkable tiles are not all connected, or that are not fair
+//! to every spawn, are thrown away and drawn again.
+
+use std::collections::VecDeque;
+use rand::Rng;
+
+use crate::config::game::MAP_OBSTACLE_PERCENT;
+use crate::game::types::{Cell, Position};
+use crate::game::rng::{GameRng, RngStream};
+use crate::game::topology::Topology;
+use super::{generate_grid, GameMap};
+
+/// Name of generated maps, offered in the map vote next to the bundled maps.
+pub const GENERATED_MAP: &str = "Generated";
+
+/// Layouts drawn before falling back to an open grid.
+const MAX_ATTEMPTS: usize = 64;
+
+/// Generate a symmetric map of the given size and topology from a seed.
+///
+/// The same seed, size and topology always give the same map. Grids smaller than 3x3 have
+/// no room for obstacles and stay open.
+pub fn generate_map(rows: usize, cols: usize, topology: Topology, seed: u64) -> GameMap {
+    let mut rng = GameRng::stream(seed, RngStream::Map);
+    let attempts = if rows >= 3 && cols >= 3 { MAX_ATTEMPTS } else { 0 };
+    (0..attempts)
+        .find_map(|_| try_generate(rows, cols, topology, &mut rng))
+        .unwrap_or_else(|| {
+            let mut map = GameMap::open(rows, cols);
+            map.name = GENERATED_MAP.to_string();
+            for pos in corners(rows, cols) {
+                map.grid[pos.y][pos.x] = Cell::SpawnPad;
+            }
+            map
+        })
+}
+
+/// Draw one layout, or `None` if its walkable tiles are not connected or no group of
+/// cannonballs on solid tiles is fair to every spawn (or there is no solid tile to hold one).
+fn try_generate(rows: usize, cols: usize, topology: Topology, rng: &mut GameRng) -> Option<GameMap> {
+    let mut grid = generate_grid(rows, cols);
+    let spawns = corners(rows, cols);
+    let domain = fundamental_domain(rows, cols, topology);
+
+    // Obstacles, keeping the spawn corners and their exits clear.
+    for &pos in &domain {
+        let near_spawn = spawns.iter().any(|&spawn| topology.distance(pos, spawn) <= 1);
+        if near_spawn || rng.random_range(0..100) >= MAP_OBSTACLE_PERCENT {
+            continue;
+        }
+        let obstacle = if rng.random_bool(0.5) { Cell::Broken } else { Cell::Wall };
+        for tile in mirrored(pos, rows, cols, topology) {
+            grid[tile.y][tile.x] = obstacle;
+        }
+    }
+    for &tile in &spawns {
+        grid[tile.y][tile.x] = Cell::SpawnPad;
+    }
+
+    let distances: Vec<_> = spawns.iter().map(|&spawn| walk_distances(&grid, topology, spawn)).collect();
+    let connected = grid.iter().enumerate().all(|(y, row)| {
+        row.iter().enumerate().all(|(x, cell)| !is_open(*cell) || distances[0][y][x].is_some())
+    });
+    if !connected {
+        return None;
+    }
+
+    // One group of mirrored cannonballs on solid tiles, at the same walking distance from
+    // every spawn.
+    // Without any solid tile left, the layout is drawn again.
+    let mut solid: Vec<Position> = domain.into_iter().filter(|p| grid[p.y][p.x] == Cell::Solid).collect();
+    while !solid.is_empty() {
+        let group = mirrored(solid.swap_remove(rng.random_range(0..solid.len())), rows, cols, topology);
+        let nearest: Vec<Option<usize>> = distances.iter()
+            .map(|d| group.iter().filter_map(|c| d[c.y][c.x]).min())
+            .collect();
+        if nearest.iter().all(|d| *d == nearest[0]) {
+            return Some(GameMap { name: GENERATED_MAP.to_string(), grid, cannonballs: group });
+        }
+    }
+    None
+}
+
+/// The four corners of the grid, without duplicates.
+fn corners(rows: usize, cols: usize) -> Vec<Position> {
+    mirrored(Position { x: 0, y: 0 }, rows, cols, Topology::Square)
+}
+
+/// Tiles drawn by the generator: their images (see [`mirrored`]) cover the whole grid.
+fn fundamental_domain(rows: usize, cols: usize, topology: Topology) -> Vec<Position> {
+    let (domain_rows, domain_cols) = match topology {
+        Topology::Square | Topology::EightWay => (rows.div_ceil(2), cols.div_ceil(2)),
+        Topology::Hex => (rows.div_ceil(2), cols),
+    };
+    (0..domain_rows)
+        .flat_map(|y| (0..domain_cols).map(move |x| Position { x, y }))
+        .collect()
+}
+
+/// A position and its images under the symmetries of a generated map, without duplicates.
+///
+/// Square boards are mirrored along both axes. In odd-r hexagonal coordinates, a
+/// top-bottom mirror keeps neighbours together only when odd rows stay odd (an odd number
+/// of rows); with an even number of rows, the board is turned half a turn instead.
+pub fn mirrored(pos: Position, rows: usize, cols: usize, topology: Topology) -> Vec<Position> {
+    let (mx, my) = (cols - 1 - pos.x, rows - 1 - pos.y);
+    let images = match topology {
+        Topology::Square | Topology::EightWay => {
+            vec![pos, Position { x: mx, y: pos.y }, Position { x: pos.x, y: my }, Position { x: mx, y: my }]
+        }
+        Topology::Hex if rows % 2 == 1 => vec![pos, Position { x: pos.x, y: my }],
+        Topology::Hex => vec![pos, Position { x: mx, y: my }],
+    };
+    let mut tiles = Vec::new();
+    for tile in images {
+        if !tiles.contains(&tile) {
+            tiles.push(tile);
+        }
+    }
+    tiles
+}
+
+/// Whether players can stand on this tile without falling.
+fn is_open(cell: Cell) -> bool {
+    cell.is_walkable() && cell != Cell::Broken
+}
+
+/// Walking distance (moves on the given topology over tiles players can stand on) from
+/// `from` to every tile, `None` for unreachable ones.
+pub fn walk_distances(grid: &[Vec<Cell>], topology: Topology, from: Position) -> Vec<Vec<Option<usize>>> {
+    let rows = grid.len();
+    let cols = grid.first().map_or(0, |row| row.len());
+    let mut distances = vec![vec![None; cols]; rows];
+    if !is_open(grid[from.y][from.x]) {
+        return distances;
+    }
+    distances[from.y][from.x] = Some(0);
+    let mut queue = VecDeque::from([from]);
+    while let Some(pos) = queue.pop_front() {
+        let next = distances[pos.y][pos.x].map(|d| d + 1);
+        for tile in topology.neighbours(pos, rows, cols) {
+            if distances[tile.y][tile.x].is_none() && is_open(grid[tile.y][tile.x]) {
+                distances[tile.y][tile.x] = next;
+                queue.push_back(tile);
+            }
+        }
+    }
+    distances
+}
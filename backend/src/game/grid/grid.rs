@@ -1,11 +1,14 @@
 //! Grid generation and tile breaking logic.
 //!
 //! This module provides functions to generate the game grid and to apply
-//! the business rules for breaking or cracking tiles at each turn, depending
-//! on the current game mode.
+//! the end-of-turn hazard of the current game mode, which breaks or cracks tiles.
 
-use crate::game::types::{Cell, Position, GameMode};
+use crate::game::types::{Cell, Position};
+use crate::game::topology::Topology;
+use crate::game::modes::mode_rules;
 use crate::game::state::GameState;
+use crate::game::events::GameEvent;
+use rand::Rng;
 use rand::seq::IteratorRandom;
 
 /// Generate a new grid of the specified size, filled with solid tiles.
@@ -13,65 +16,91 @@ pub fn generate_grid(rows: usize, cols: usize) -> Vec<Vec<Cell>> {
     vec![vec![Cell::Solid; cols]; rows]
 }
 
-/// Apply the tile breaking logic for the current turn, depending on the game mode.
+/// Apply the end-of-turn hazard of the current game mode (see [`ModeRules`](crate::game::modes::ModeRules)).
 ///
-/// - In Classic mode: randomly select a solid tile and break it.
-/// - In Cracked mode: Randomly select a solid tile and turn it into a cracked tile. + All cracked tiles become broken.
-pub fn break_tile(game_state: &mut GameState) {
-    let mut rng = rand::rng();
-
-    match game_state.mode {
-        GameMode::Classic => {
-            // Collect all coordinates of solid tiles.
-            let solid_tiles: Vec<(usize, usize)> = game_state.grid.iter().enumerate()
-                .flat_map(|(y, row)| row.iter().enumerate().filter_map(move |(x, cell)| {
-                    if *cell == Cell::Solid {
-                        Some((y, x))
-                    } else {
-                        None
-                    }
-                }))
-                .collect();
+/// Walls and spawn pads never break.
+pub fn break_tile(game_state: &mut GameState, events: &mut Vec<GameEvent>) {
+    mode_rules(game_state.mode).end_of_turn_hazard(game_state, events);
+}
 
-            // If there is at least one solid tile, randomly select one to break.
-            if let Some(&(y, x)) = solid_tiles.iter().choose(&mut rng) {
-                // If a cannonball is present on the tile to be broken, remove it.
-                if let Some(pos) = game_state.cannonballs.iter().position(|c| c.pos == Position { x, y }) {
-                    game_state.cannonballs.remove(pos);
-                }
-                // Mark the selected tile as broken.
-                game_state.grid[y][x] = Cell::Broken;
+/// Break every cracked tile, as announced one turn earlier.
+pub fn break_cracked_tiles(game_state: &mut GameState, events: &mut Vec<GameEvent>) {
+    for (y, row) in game_state.grid.iter_mut().enumerate() {
+        for (x, cell) in row.iter_mut().enumerate() {
+            if *cell == Cell::Cracked {
+                *cell = Cell::Broken;
+                events.push(GameEvent::TileBroken { pos: Position { x, y } });
             }
         }
-        GameMode::Cracked => {
-            // Step 1: All cracked tiles become broken.
-            for row in game_state.grid.iter_mut() {
-                for cell in row.iter_mut() {
-                    if *cell == Cell::Cracked {
-                        *cell = Cell::Broken;
-                    }
-                }
-            }
-            // Step 2: Collect all solid tiles to select one to crack.
-            let solid_tiles: Vec<(usize, usize)> = game_state.grid.iter().enumerate()
-                .flat_map(|(y, row)| row.iter().enumerate().filter_map(move |(x, cell)| {
-                    if *cell == Cell::Solid {
-                        Some((y, x))
-                    } else {
-                        None
-                    }
-                }))
-                .collect();
+    }
+}
 
-            // If there is at least one solid tile, randomly select one to crack.
-            if let Some(&(y, x)) = solid_tiles.iter().choose(&mut rng) {
-                // If a cannonball is present on the tile to be cracked, remove it.
-                if let Some(pos) = game_state.cannonballs.iter().position(|c| c.pos == Position { x, y }) {
-                    game_state.cannonballs.remove(pos);
-                }
-                // Mark the selected tile as cracked.
-                game_state.grid[y][x] = Cell::Cracked;
+/// Crack the tile at `pos` so that it breaks at the end of the next turn, destroying
+/// any cannonball or item lying on it.
+pub fn crack_tile(game_state: &mut GameState, pos: Position, events: &mut Vec<GameEvent>) {
+    remove_cannonball_at(game_state, pos, events);
+    remove_item_at(game_state, pos, events);
+    game_state.grid[pos.y][pos.x] = Cell::Cracked;
+    events.push(GameEvent::TileCracked { pos });
+}
+
+/// One step of the lava cellular automaton: each broken tile sets fire to each intact tile
+/// sharing an edge with it (see [`Topology::edge_neighbours`]) with a `chance` percent
+/// probability (always at 100), so a tile next to several lava tiles is more likely to catch
+/// fire.
+///
+/// Neighbourhoods are read from the grid as it is before the step, so lava spreads by at
+/// most one tile per turn. Tiles are visited in row-major order to keep draws deterministic.
+pub fn lava_spread(grid: &[Vec<Cell>], topology: Topology, chance: u8, rng: &mut impl Rng) -> Vec<Position> {
+    let rows = grid.len();
+    let cols = grid.first().map_or(0, |row| row.len());
+    let mut spread = Vec::new();
+    for (y, row) in grid.iter().enumerate() {
+        for (x, cell) in row.iter().enumerate() {
+            if !cell.is_intact() {
+                continue;
+            }
+            let lava_neighbours = topology.edge_neighbours(Position { x, y }, rows, cols)
+                .iter()
+                .filter(|n| grid[n.y][n.x] == Cell::Broken)
+                .count();
+            if (0..lava_neighbours).any(|_| rng.random_range(0..100) < chance) {
+                spread.push(Position { x, y });
             }
         }
     }
+    spread
+}
+
+/// Randomly pick up to `tiles_broken_per_turn` distinct intact tiles (solid, ice and
+/// teleporter tiles).
+pub fn choose_intact_tiles(game_state: &mut GameState) -> Vec<Position> {
+    let intact_tiles: Vec<Position> = game_state.grid.iter().enumerate()
+        .flat_map(|(y, row)| row.iter().enumerate().filter_map(move |(x, cell)| {
+            if cell.is_intact() {
+                Some(Position { x, y })
+            } else {
+                None
+            }
+        }))
+        .collect();
+
+    let count = game_state.rules.tiles_broken_per_turn;
+    intact_tiles.into_iter().choose_multiple(&mut game_state.rng, count)
+}
+
+/// Remove the item lying at `pos`, if any, recording its destruction.
+pub fn remove_item_at(game_state: &mut GameState, pos: Position, events: &mut Vec<GameEvent>) {
+    if let Some(index) = game_state.items.iter().position(|i| i.pos == pos) {
+        let item = game_state.items.remove(index);
+        events.push(GameEvent::ItemDestroyed { pos, item: item.kind });
+    }
+}
+
+/// Remove the cannonball lying at `pos`, if any, recording its destruction.
+pub fn remove_cannonball_at(game_state: &mut GameState, pos: Position, events: &mut Vec<GameEvent>) {
+    if let Some(index) = game_state.cannonballs.iter().position(|c| c.pos == pos) {
+        game_state.cannonballs.remove(index);
+        events.push(GameEvent::CannonballDestroyed { pos });
+    }
 }
\ No newline at end of file
@@ -0,0 +1,130 @@
+//! Map file format and bundled maps.
+//!
+//! A map is a text file: a `name:` line, then one line per row of the grid with one
+//! character per tile:
+//!
+//! | Char  | Tile                                                        |
+//! |-------|-------------------------------------------------------------|
+//! | `.`   | Solid                                                       |
+//! | `x`   | Broken (a hole, or outside the map)                         |
+//! | `c`   | Cracked                                                     |
+//! | `i`   | Ice                                                         |
+//! | `#`   | Wall                                                        |
+//! | `S`   | Spawn pad (fixed spawn point)                               |
+//! | `H`   | Hill                                                        |
+//! | `o`   | Solid, with a cannonball on it at the start of the game     |
+//! | `0-9` | Teleporter of that channel (each channel needs two tiles)   |
+//!
+//! Rows shorter than the widest one are padded with broken tiles, so maps do not have to
+//! be rectangles. Blank lines are ignored.
+
+use crate::game::types::{Cell, Position};
+use crate::game::errors::MapError;
+use crate::game::topology::Topology;
+use super::{generate_grid, generate_map, GENERATED_MAP};
+
+/// Maps shipped with the server, in the order they are offered to players.
+static BUNDLED_MAPS: &[&str] = &[
+    include_str!("../../../maps/donut.map"),
+    include_str!("../../../maps/cross.map"),
+    include_str!("../../../maps/frozen_lake.map"),
+];
+
+/// A playable map: its tiles and where cannonballs lie at the start of the game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameMap {
+    /// Display name, used in the map vote.
+    pub name: String,
+    /// Rows of cells.
+    pub grid: Vec<Vec<Cell>>,
+    /// Initial cannonball positions (random ones are spawned when empty).
+    pub cannonballs: Vec<Position>,
+}
+
+impl GameMap {
+    /// Rectangle of solid tiles without fixed cannonballs, the default when no map is chosen.
+    pub fn open(rows: usize, cols: usize) -> Self {
+        Self { name: "Open".to_string(), grid: generate_grid(rows, cols), cannonballs: Vec::new() }
+    }
+
+    /// Parse a map from its text format (see the module documentation).
+    pub fn parse(text: &str) -> Result<Self, MapError> {
+        let mut lines = text.lines().map(str::trim_end).filter(|line| !line.is_empty());
+        let name = lines.next()
+            .and_then(|line| line.strip_prefix("name:"))
+            .map(|name| name.trim().to_string())
+            .ok_or(MapError::MissingName)?;
+
+        let mut grid: Vec<Vec<Cell>> = Vec::new();
+        let mut cannonballs = Vec::new();
+        for (y, line) in lines.enumerate() {
+            let mut row = Vec::new();
+            for (x, tile) in line.chars().enumerate() {
+                let cell = match tile {
+                    '.' => Cell::Solid,
+                    'x' => Cell::Broken,
+                    'c' => Cell::Cracked,
+                    'i' => Cell::Ice,
+                    '#' => Cell::Wall,
+                    'S' => Cell::SpawnPad,
+                    'H' => Cell::Hill,
+                    'o' => {
+                        cannonballs.push(Position { x, y });
+                        Cell::Solid
+                    }
+                    '0'..='9' => Cell::Teleporter { channel: tile as u8 - b'0' },
+                    _ => return Err(MapError::UnknownTile { row: y, col: x, tile }),
+                };
+                row.push(cell);
+            }
+            grid.push(row);
+        }
+
+        let cols = grid.iter().map(|row| row.len()).max().unwrap_or(0);
+        if cols == 0 {
+            return Err(MapError::Empty);
+        }
+        for row in &mut grid {
+            row.resize(cols, Cell::Broken);
+        }
+        for channel in 0..=9 {
+            let tiles = grid.iter().flatten().filter(|c| **c == Cell::Teleporter { channel }).count();
+            if tiles != 0 && tiles != 2 {
+                return Err(MapError::UnpairedTeleporter { channel });
+            }
+        }
+
+        Ok(Self { name, grid, cannonballs })
+    }
+}
+
+/// All bundled maps.
+///
+/// # Panics
+/// Panics if a bundled map file is malformed (caught by the tests).
+pub fn bundled_maps() -> Vec<GameMap> {
+    BUNDLED_MAPS.iter()
+        .map(|text| GameMap::parse(text).unwrap_or_else(|e| panic!("invalid bundled map: {}", e)))
+        .collect()
+}
+
+/// Names of the maps offered in the map vote: the bundled maps, then the generated one.
+pub fn map_names() -> Vec<String> {
+    let mut names: Vec<String> = bundled_maps().into_iter().map(|map| map.name).collect();
+    names.push(GENERATED_MAP.to_string());
+    names
+}
+
+/// The map with this name: a bundled map, or a map generated from `seed` with the given
+/// size and topology (see [`generate_map`]).
+pub fn map_by_name(name: &str, rows: usize, cols: usize, topology: Topology, seed: u64) -> Option<GameMap> {
+    if name == GENERATED_MAP {
+        return Some(generate_map(rows, cols, topology, seed));
+    }
+    bundled_map(name)
+}
+
+/// The bundled map with this name, if any.
+pub fn bundled_map(name: &str) -> Option<GameMap> {
+    bundled_maps().into_iter().find(|map| map.name == name)
+}
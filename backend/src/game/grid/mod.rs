@@ -1,7 +1,13 @@
 //! Grid module.
 //!
-//! This module provides grid generation and tile breaking logic.
+//! This module provides grid generation, tile breaking logic, map loading and
+//! procedural map generation.
 
+#[allow(clippy::module_inception)]
 pub mod grid;
+pub mod map;
+pub mod generator;
 
-pub use grid::*;
\ No newline at end of file
+pub use grid::*;
+pub use map::*;
+pub use generator::*;
\ No newline at end of file
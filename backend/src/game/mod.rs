@@ -6,9 +6,19 @@
 pub mod types;
 pub mod utils;
 pub mod state;
-pub mod tests;
+pub mod events;
+pub mod errors;
+pub mod rng;
+pub mod topology;
+#[cfg(test)]
+mod tests;
 
 pub mod entities;
 pub mod grid;
 pub mod systems;
-// pub mod demo;
+pub mod modes;
+pub mod view;
+pub mod agents;
+pub mod simulation;
+pub mod replay;
+pub mod demo;
@@ -0,0 +1,27 @@
+//! Classic mode: tiles break at random at the end of each turn.
+
+use crate::game::types::{Cell, GameMode};
+use crate::game::state::GameState;
+use crate::game::events::GameEvent;
+use crate::game::grid::{choose_intact_tiles, remove_cannonball_at, remove_item_at};
+use super::ModeRules;
+
+/// Randomly selects `tiles_broken_per_turn` intact tiles and breaks them each turn.
+pub struct Classic;
+
+impl ModeRules for Classic {
+    fn mode(&self) -> GameMode {
+        GameMode::Classic
+    }
+
+    fn end_of_turn_hazard(&self, game_state: &mut GameState, events: &mut Vec<GameEvent>) {
+        for pos in choose_intact_tiles(game_state) {
+            // If a cannonball or an item is present on the tile to be broken, remove it.
+            remove_cannonball_at(game_state, pos, events);
+            remove_item_at(game_state, pos, events);
+            // Mark the selected tile as broken.
+            game_state.grid[pos.y][pos.x] = Cell::Broken;
+            events.push(GameEvent::TileBroken { pos });
+        }
+    }
+}
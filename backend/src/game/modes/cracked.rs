@@ -0,0 +1,25 @@
+//! Cracked mode: tiles crack first and break one turn later, so players see it coming.
+
+use crate::game::types::GameMode;
+use crate::game::state::GameState;
+use crate::game::events::GameEvent;
+use crate::game::grid::{choose_intact_tiles, break_cracked_tiles, crack_tile};
+use super::ModeRules;
+
+/// All cracked tiles become broken, then `tiles_broken_per_turn` intact tiles crack.
+pub struct Cracked;
+
+impl ModeRules for Cracked {
+    fn mode(&self) -> GameMode {
+        GameMode::Cracked
+    }
+
+    fn end_of_turn_hazard(&self, game_state: &mut GameState, events: &mut Vec<GameEvent>) {
+        // Step 1: All cracked tiles become broken.
+        break_cracked_tiles(game_state, events);
+        // Step 2: Randomly select intact tiles to crack.
+        for pos in choose_intact_tiles(game_state) {
+            crack_tile(game_state, pos, events);
+        }
+    }
+}
@@ -0,0 +1,24 @@
+//! Fog of war mode: players only see the tiles within their vision radius.
+
+use crate::config::game::GameRules;
+use crate::game::types::GameMode;
+use crate::game::state::GameState;
+use crate::game::events::GameEvent;
+use super::{ModeRules, Classic};
+
+/// Classic tile breaking; each player sees `vision_radius` tiles around them.
+pub struct FogOfWar;
+
+impl ModeRules for FogOfWar {
+    fn mode(&self) -> GameMode {
+        GameMode::FogOfWar
+    }
+
+    fn end_of_turn_hazard(&self, game_state: &mut GameState, events: &mut Vec<GameEvent>) {
+        Classic.end_of_turn_hazard(game_state, events);
+    }
+
+    fn vision_radius(&self, rules: &GameRules) -> Option<usize> {
+        Some(rules.vision_radius)
+    }
+}
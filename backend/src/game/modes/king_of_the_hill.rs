@@ -0,0 +1,101 @@
+//! King of the hill mode: players score by standing alone on the hill in the center of
+//! the grid, while tiles keep breaking as in Classic mode.
+
+use crate::game::types::{Cell, GameMode, Player};
+use crate::game::state::{GameState, GameOutcome, GameResult, Placement};
+use crate::game::events::GameEvent;
+use super::{ModeRules, Classic};
+
+/// A living player alone on the hill at the end of a turn scores a point. The game ends
+/// once a player reaches `target_score`, after `turn_limit` turns, or when fewer than two
+/// players are alive; the best score wins.
+pub struct KingOfTheHill;
+
+impl ModeRules for KingOfTheHill {
+    fn mode(&self) -> GameMode {
+        GameMode::KingOfTheHill
+    }
+
+    /// Unless the map has its own hill, the hill covers the center of the grid: one tile
+    /// per odd dimension, two per even one. Only intact walkable tiles (solid or ice) become
+    /// hill; when no center tile is one, the hill goes on the one nearest to the center.
+    fn prepare_grid(&self, grid: &mut [Vec<Cell>]) {
+        let rows = grid.len();
+        let cols = grid.first().map_or(0, |row| row.len());
+        if rows == 0 || cols == 0 || grid.iter().flatten().any(|c| *c == Cell::Hill) {
+            return;
+        }
+        let can_hold_hill = |cell: Cell| matches!(cell, Cell::Solid | Cell::Ice);
+        let mut placed = false;
+        for row in &mut grid[(rows - 1) / 2..=rows / 2] {
+            for cell in &mut row[(cols - 1) / 2..=cols / 2] {
+                if can_hold_hill(*cell) {
+                    *cell = Cell::Hill;
+                    placed = true;
+                }
+            }
+        }
+        if placed {
+            return;
+        }
+
+        // Distances are doubled so the center of an even dimension stays an integer.
+        let distance = |y: usize, x: usize| {
+            let dy = (2 * y).abs_diff(rows - 1);
+            let dx = (2 * x).abs_diff(cols - 1);
+            dy * dy + dx * dx
+        };
+        let nearest = (0..rows)
+            .flat_map(|y| (0..cols).map(move |x| (y, x)))
+            .filter(|&(y, x)| can_hold_hill(grid[y][x]))
+            .min_by_key(|&(y, x)| distance(y, x));
+        if let Some((y, x)) = nearest {
+            grid[y][x] = Cell::Hill;
+        }
+    }
+
+    fn end_of_turn_hazard(&self, game_state: &mut GameState, events: &mut Vec<GameEvent>) {
+        let on_hill = |p: &Player| p.is_alive && game_state.grid[p.pos.y][p.pos.x] == Cell::Hill;
+        let kings: Vec<usize> = (0..game_state.players.len())
+            .filter(|&i| on_hill(&game_state.players[i]))
+            .collect();
+        if let [king] = kings[..] {
+            let player = &mut game_state.players[king];
+            player.score += 1;
+            events.push(GameEvent::PointScored { player_id: player.id, pos: player.pos, score: player.score });
+        }
+
+        Classic.end_of_turn_hazard(game_state, events);
+    }
+
+    fn outcome(&self, game_state: &GameState) -> Option<GameOutcome> {
+        let players = &game_state.players;
+        let target_reached = players.iter().any(|p| p.score >= game_state.rules.target_score);
+        let time_up = game_state.turn > game_state.rules.turn_limit;
+        let contested = players.iter().filter(|p| p.is_alive).count() > 1;
+        if !target_reached && !time_up && contested {
+            return None;
+        }
+
+        let mut ranked: Vec<&Player> = players.iter().collect();
+        ranked.sort_by(|a, b| b.score.cmp(&a.score).then(a.id.cmp(&b.id)));
+        let placements: Vec<Placement> = ranked.iter()
+            .map(|p| Placement {
+                player_id: p.id,
+                username: p.username.clone(),
+                team_id: p.team_id,
+                rank: 1 + ranked.iter().filter(|o| o.score > p.score).count() as u8,
+                eliminated_turn: p.eliminated_turn,
+            })
+            .collect();
+
+        let winners: Vec<u8> = placements.iter().filter(|p| p.rank == 1).map(|p| p.player_id).collect();
+        let result = match winners[..] {
+            [player_id] => GameResult::Winner { player_id },
+            _ => GameResult::Draw { player_ids: winners },
+        };
+
+        Some(GameOutcome { result, placements })
+    }
+}
+
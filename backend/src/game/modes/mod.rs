@@ -0,0 +1,162 @@
+//! Game modes.
+//!
+//! Each mode implements [`ModeRules`] in its own module and is listed once in [`MODES`].
+//! The game core asks the registry for the rules of the current [`GameMode`] instead of
+//! matching on it, and the mode vote offers every registered mode. Adding a mode means
+//! adding its `GameMode` variant, its module, and one entry in [`MODES`].
+
+pub mod classic;
+pub mod cracked;
+pub mod shrinking_ring;
+pub mod spreading_lava;
+pub mod teams;
+pub mod king_of_the_hill;
+pub mod fog_of_war;
+
+use crate::config::game::{GameRules, SpawnSchedule};
+use crate::game::types::{Cell, GameMode, ItemKind, Player, SpawnStrategy};
+use crate::game::state::{GameState, GameOutcome, GameResult, Placement};
+use crate::game::events::GameEvent;
+use crate::server::game_session::messages::PlayerAction;
+
+pub use classic::Classic;
+pub use cracked::Cracked;
+pub use shrinking_ring::ShrinkingRing;
+pub use spreading_lava::SpreadingLava;
+pub use teams::Teams;
+pub use king_of_the_hill::KingOfTheHill;
+pub use fog_of_war::FogOfWar;
+
+/// Behaviour that differs from one game mode to another.
+///
+/// Only the end-of-turn hazard is mandatory; every other hook defaults to the standard
+/// rules (spawns from [`GameRules`], last player standing wins, every action allowed).
+pub trait ModeRules: Sync {
+    /// Identifier of the mode, as sent to clients.
+    fn mode(&self) -> GameMode;
+
+    /// Add the mode's special tiles to a freshly generated grid, before players spawn.
+    fn prepare_grid(&self, _grid: &mut [Vec<Cell>]) {}
+
+    /// Apply the mode's hazard at the end of each turn (e.g., break or crack tiles, award
+    /// points).
+    fn end_of_turn_hazard(&self, game_state: &mut GameState, events: &mut Vec<GameEvent>);
+
+    /// When cannonballs respawn during the game.
+    fn cannonball_schedule(&self, rules: &GameRules) -> SpawnSchedule {
+        rules.cannonball_schedule_for(self.mode())
+    }
+
+    /// How players are placed at the start of the game.
+    fn spawn_strategy(&self, rules: &GameRules) -> SpawnStrategy {
+        rules.spawn_strategy_for(self.mode())
+    }
+
+    /// Items that can spawn during the game.
+    fn item_pool<'a>(&self, rules: &'a GameRules) -> &'a [ItemKind] {
+        rules.item_pool_for(self.mode())
+    }
+
+    /// Outcome of the game if it is over.
+    fn outcome(&self, game_state: &GameState) -> Option<GameOutcome> {
+        last_player_standing(game_state)
+    }
+
+    /// Whether players play in the teams formed by matchmaking.
+    fn team_play(&self) -> bool {
+        false
+    }
+
+    /// How far players see, or `None` when they see the whole game (see
+    /// [`Vision`](crate::game::view::Vision)).
+    fn vision_radius(&self, _rules: &GameRules) -> Option<usize> {
+        None
+    }
+
+    /// Whether players may take this kind of action in this mode.
+    fn allows_action(&self, _action: &PlayerAction) -> bool {
+        true
+    }
+}
+
+/// All playable modes, in the order they are offered to players.
+pub static MODES: &[&dyn ModeRules] = &[&Classic, &Cracked, &ShrinkingRing, &SpreadingLava, &Teams, &KingOfTheHill, &FogOfWar];
+
+/// Modes offered in the mode vote.
+pub fn available_modes() -> Vec<GameMode> {
+    MODES.iter().map(|m| m.mode()).collect()
+}
+
+/// Rules of the given mode.
+///
+/// # Panics
+/// Panics if the mode was not added to [`MODES`].
+pub fn mode_rules(mode: GameMode) -> &'static dyn ModeRules {
+    *MODES.iter()
+        .find(|m| m.mode() == mode)
+        .unwrap_or_else(|| panic!("game mode {:?} is not registered", mode))
+}
+
+/// Standard win condition: the game is over once at most one player is alive.
+///
+/// Players are ranked by how long they survived: the survivor first, then by elimination
+/// turn, latest first. When the last players are all eliminated during the same turn the
+/// game is a draw between them.
+pub fn last_player_standing(game_state: &GameState) -> Option<GameOutcome> {
+    last_side_standing(game_state, |p| (None, p.id))
+}
+
+/// Team win condition: the game is over once at most one team is alive.
+///
+/// Teammates share the rank of their longest-surviving member. Players without a team
+/// play on their own.
+pub fn last_team_standing(game_state: &GameState) -> Option<GameOutcome> {
+    last_side_standing(game_state, |p| match p.team_id {
+        Some(team_id) => (Some(team_id), 0),
+        None => (None, p.id),
+    })
+}
+
+/// Shared win condition: the game is over once at most one side has living players.
+///
+/// `side` tells which side a player belongs to. A side survives as long as its best
+/// member, and sides are ranked like players in [`last_player_standing`].
+fn last_side_standing(game_state: &GameState, side: impl Fn(&Player) -> (Option<u8>, u8)) -> Option<GameOutcome> {
+    let players = &game_state.players;
+    let mut alive_sides: Vec<(Option<u8>, u8)> = players.iter().filter(|p| p.is_alive).map(&side).collect();
+    alive_sides.sort();
+    alive_sides.dedup();
+    if alive_sides.len() > 1 {
+        return None;
+    }
+
+    // Survivors (`None`) rank before any eliminated player, then latest elimination first.
+    let survival = |p: &Player| {
+        players.iter()
+            .filter(|o| side(o) == side(p))
+            .map(|o| o.eliminated_turn.unwrap_or(u32::MAX))
+            .max()
+            .unwrap_or(0)
+    };
+    let mut ranked: Vec<&Player> = players.iter().collect();
+    ranked.sort_by(|a, b| survival(b).cmp(&survival(a)).then(a.id.cmp(&b.id)));
+
+    let placements: Vec<Placement> = ranked.iter()
+        .map(|p| Placement {
+            player_id: p.id,
+            username: p.username.clone(),
+            team_id: p.team_id,
+            rank: 1 + ranked.iter().filter(|o| survival(o) > survival(p)).count() as u8,
+            eliminated_turn: p.eliminated_turn,
+        })
+        .collect();
+
+    let winners: Vec<u8> = placements.iter().filter(|p| p.rank == 1).map(|p| p.player_id).collect();
+    let result = match alive_sides.first() {
+        Some((Some(team_id), _)) => GameResult::TeamWinner { team_id: *team_id, player_ids: winners },
+        Some((None, player_id)) => GameResult::Winner { player_id: *player_id },
+        None => GameResult::Draw { player_ids: winners },
+    };
+
+    Some(GameOutcome { result, placements })
+}
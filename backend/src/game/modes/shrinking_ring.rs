@@ -0,0 +1,53 @@
+//! Shrinking ring mode: instead of random breaks, the grid crumbles from the outside in.
+//!
+//! Every `ring_shrink_interval` turns the outermost ring still standing cracks, and it
+//! breaks at the end of the next turn, like in Cracked mode. Players are pushed toward the
+//! center on a predictable schedule.
+
+use crate::game::types::{Position, GameMode};
+use crate::game::state::GameState;
+use crate::game::events::GameEvent;
+use crate::game::grid::{break_cracked_tiles, crack_tile};
+use super::ModeRules;
+
+/// Cracks the outer ring on a schedule, then breaks it one turn later.
+pub struct ShrinkingRing;
+
+/// Index of the ring a tile belongs to: 0 for the border, 1 for the tiles just inside it...
+pub fn ring_index(pos: Position, rows: usize, cols: usize) -> usize {
+    pos.x.min(pos.y).min(cols - 1 - pos.x).min(rows - 1 - pos.y)
+}
+
+impl ModeRules for ShrinkingRing {
+    fn mode(&self) -> GameMode {
+        GameMode::ShrinkingRing
+    }
+
+    fn end_of_turn_hazard(&self, game_state: &mut GameState, events: &mut Vec<GameEvent>) {
+        // Step 1: The ring cracked last turn breaks.
+        break_cracked_tiles(game_state, events);
+
+        // Step 2: On schedule, the next ring cracks.
+        let interval = game_state.rules.ring_shrink_interval;
+        if interval == 0 || !game_state.turn.is_multiple_of(interval) {
+            return;
+        }
+        // The outermost ring with intact tiles left, whatever broke it before (holes and
+        // walls of the map, shots, earlier rings).
+        let rows = game_state.grid.len();
+        let cols = game_state.grid.first().map_or(0, |row| row.len());
+        let intact: Vec<Position> = (0..rows)
+            .flat_map(|y| (0..cols).map(move |x| Position { x, y }))
+            .filter(|&pos| game_state.grid[pos.y][pos.x].is_intact())
+            .collect();
+        let Some(ring) = intact.iter().map(|&pos| ring_index(pos, rows, cols)).min() else {
+            return;
+        };
+        let tiles: Vec<Position> = intact.into_iter()
+            .filter(|&pos| ring_index(pos, rows, cols) == ring)
+            .collect();
+        for pos in tiles {
+            crack_tile(game_state, pos, events);
+        }
+    }
+}
@@ -0,0 +1,33 @@
+//! Spreading lava mode: broken tiles are lava pools that grow from turn to turn.
+
+use crate::game::types::{Cell, GameMode};
+use crate::game::state::GameState;
+use crate::game::events::GameEvent;
+use crate::game::grid::{break_cracked_tiles, choose_intact_tiles, crack_tile, lava_spread};
+use super::ModeRules;
+
+/// Lava spreads from broken tiles to their orthogonal neighbours, which crack one turn
+/// before breaking. While the grid has no lava, random tiles crack to start new pools.
+pub struct SpreadingLava;
+
+impl ModeRules for SpreadingLava {
+    fn mode(&self) -> GameMode {
+        GameMode::SpreadingLava
+    }
+
+    fn end_of_turn_hazard(&self, game_state: &mut GameState, events: &mut Vec<GameEvent>) {
+        // Step 1: Tiles that caught fire last turn break.
+        break_cracked_tiles(game_state, events);
+
+        // Step 2: Lava spreads, or a new pool starts on an untouched grid.
+        let has_lava = game_state.grid.iter().flatten().any(|cell| *cell == Cell::Broken);
+        let tiles = if has_lava {
+            lava_spread(&game_state.grid, game_state.rules.topology, game_state.rules.lava_spread_chance, &mut game_state.rng)
+        } else {
+            choose_intact_tiles(game_state)
+        };
+        for pos in tiles {
+            crack_tile(game_state, pos, events);
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! Teams mode: players play in the teams formed by matchmaking.
+
+use crate::game::types::GameMode;
+use crate::game::state::{GameState, GameOutcome};
+use crate::game::events::GameEvent;
+use super::{ModeRules, Classic, last_team_standing};
+
+/// Classic tile breaking; the last team with a living member wins.
+pub struct Teams;
+
+impl ModeRules for Teams {
+    fn mode(&self) -> GameMode {
+        GameMode::Teams
+    }
+
+    fn end_of_turn_hazard(&self, game_state: &mut GameState, events: &mut Vec<GameEvent>) {
+        Classic.end_of_turn_hazard(game_state, events);
+    }
+
+    fn outcome(&self, game_state: &GameState) -> Option<GameOutcome> {
+        last_team_standing(game_state)
+    }
+
+    fn team_play(&self) -> bool {
+        true
+    }
+}
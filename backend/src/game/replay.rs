@@ -0,0 +1,152 @@
+//! Game replays.
+//!
+//! A [`Replay`] records everything needed to play a game again: its rules, seed, mode, map
+//! and players, then the actions resolved each turn (including the `Stay`s given to players
+//! who did not act in time). Since the game core is deterministic, stepping a fresh state
+//! through the recorded actions rebuilds every turn exactly. Each recorded turn also carries
+//! a hash of the state it led to, so [`Replay::verify`] can tell whether a build still plays
+//! the game the same way, and on which turn it diverges.
+//!
+//! Game sessions write the replay of every finished game as JSON (see
+//! [`REPLAY_DIR`](crate::config::game::REPLAY_DIR)); the `replay` binary verifies such files.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Serialize, Deserialize};
+
+use crate::config::game::GameRules;
+use crate::game::errors::ReplayError;
+use crate::game::grid::map_by_name;
+use crate::game::state::{GameOutcome, GameState};
+use crate::game::types::GameMode;
+use crate::server::game_session::messages::PlayerAction;
+use crate::server::matchmaking::types::PlayerInfo;
+
+/// Actions resolved on one turn, and the state they led to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayTurn {
+    /// Turn number (starts at 1).
+    pub turn: u32,
+    /// Actions of the living players, by player ID, in the order they were resolved.
+    pub actions: Vec<(u8, PlayerAction)>,
+    /// [`state_hash`] of the state after the turn.
+    pub state_hash: u64,
+}
+
+/// Full record of a game, enough to rebuild every one of its states.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    /// Rule set of the game.
+    pub rules: GameRules,
+    /// Seed of the game.
+    pub seed: u64,
+    /// Mode of the game.
+    pub mode: GameMode,
+    /// Name of the map the game was played on, or `None` for an open grid.
+    pub map: Option<String>,
+    /// Players in spawn order.
+    pub players: Vec<PlayerInfo>,
+    /// [`state_hash`] of the initial state.
+    pub initial_hash: u64,
+    /// Resolved turns, in order.
+    pub turns: Vec<ReplayTurn>,
+    /// Outcome of the game, once it is over.
+    pub outcome: Option<GameOutcome>,
+}
+
+impl Replay {
+    /// Start recording a game from its initial state, created on the map with this name
+    /// (`None` for an open grid).
+    pub fn new(initial: &GameState, players: Vec<PlayerInfo>, map: Option<String>) -> Self {
+        Self {
+            rules: initial.rules.clone(),
+            seed: initial.seed,
+            mode: initial.mode,
+            map,
+            players,
+            initial_hash: state_hash(initial),
+            turns: Vec::new(),
+            outcome: None,
+        }
+    }
+
+    /// Record a resolved turn: the actions given to [`GameState::step`] and the state it
+    /// returned. The outcome is updated when the game is over.
+    pub fn record_turn(&mut self, actions: &[(u8, PlayerAction)], next: &GameState) {
+        self.turns.push(ReplayTurn {
+            turn: next.turn - 1,
+            actions: actions.to_vec(),
+            state_hash: state_hash(next),
+        });
+        self.outcome = next.outcome();
+    }
+
+    /// Rebuild the initial state of the game.
+    pub fn initial_state(&self) -> Result<GameState, ReplayError> {
+        let (rows, cols) = (self.rules.grid_rows, self.rules.grid_cols);
+        let state = match &self.map {
+            Some(name) => {
+                let map = map_by_name(name, rows, cols, self.rules.topology, self.seed)
+                    .ok_or_else(|| ReplayError::UnknownMap { name: name.clone() })?;
+                GameState::new_on_map(self.rules.clone(), self.players.clone(), self.mode, &map, self.seed)
+            }
+            None => GameState::new(self.rules.clone(), self.players.clone(), self.mode, self.seed),
+        };
+        Ok(state)
+    }
+
+    /// Play the game again and check every state against its recorded hash, and the final
+    /// outcome against the recorded one. Returns the final state.
+    pub fn verify(&self) -> Result<GameState, ReplayError> {
+        let mut state = self.initial_state()?;
+        check_hash(0, self.initial_hash, &state)?;
+        for turn in &self.turns {
+            state = state.step(&turn.actions).0;
+            check_hash(turn.turn, turn.state_hash, &state)?;
+        }
+        if state.outcome() != self.outcome {
+            return Err(ReplayError::OutcomeMismatch);
+        }
+        Ok(state)
+    }
+
+    /// Write the replay as JSON to `path`, creating its parent directories.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_vec(self).map_err(io::Error::other)?;
+        fs::write(path, json)
+    }
+
+    /// Read a replay written by [`Replay::save`].
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let json = fs::read(path)?;
+        serde_json::from_slice(&json).map_err(io::Error::other)
+    }
+}
+
+/// Path of the replay file of a game in `dir`.
+pub fn replay_path(dir: &Path, game_id: &str) -> PathBuf {
+    dir.join(format!("{}.json", game_id))
+}
+
+/// Stable 64-bit hash of a state: FNV-1a over its JSON form, so that it does not depend on
+/// the platform or the build.
+pub fn state_hash(state: &GameState) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    let json = serde_json::to_vec(state).expect("game state is always serializable");
+    json.iter().fold(OFFSET_BASIS, |hash, &byte| (hash ^ byte as u64).wrapping_mul(PRIME))
+}
+
+/// Compare the hash of the state after `turn` with the recorded one.
+fn check_hash(turn: u32, expected: u64, state: &GameState) -> Result<(), ReplayError> {
+    let actual = state_hash(state);
+    if actual != expected {
+        return Err(ReplayError::StateMismatch { turn, expected, actual });
+    }
+    Ok(())
+}
@@ -0,0 +1,78 @@
+//! Deterministic random number generation for the game core.
+//!
+//! Every random decision taken by the game logic (spawns, tile breaks, mode pick) draws
+//! from a [`GameRng`] seeded from the game's seed, so that the same seed and the same
+//! sequence of player actions always reproduce the exact same game.
+//!
+//! The game seed is never used as is: each part of the game (the state, the mode pick, map
+//! generation, team assignment) draws from its own [`RngStream`], seeded with a sub-seed
+//! derived from the game seed, so that the draws of one stream tell nothing about another.
+
+use rand::RngCore;
+use serde::{Serialize, Deserialize};
+
+/// Independent random streams of a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RngStream {
+    /// Spawns, tile breaks and every other draw of the game state.
+    State,
+    /// Mode and map pick of the mode choice phase.
+    ModeChoice,
+    /// Procedural map generation.
+    Map,
+    /// Team assignment of locally seated players (simulator, demo).
+    Teams,
+}
+
+impl RngStream {
+    /// Arbitrary constant mixed into the game seed to derive the stream's sub-seed.
+    fn salt(self) -> u64 {
+        match self {
+            RngStream::State => 0x5DEE_CE66_D1CE_4E5B,
+            RngStream::ModeChoice => 0xA076_1D64_78BD_642F,
+            RngStream::Map => 0xE703_7ED1_A0B4_28DB,
+            RngStream::Teams => 0x8EBC_6AF0_9C88_C6E3,
+        }
+    }
+}
+
+/// Seedable SplitMix64 generator with a serializable 64-bit state.
+///
+/// Implemented in the crate rather than relying on `StdRng`, whose algorithm is not
+/// guaranteed to stay the same between `rand` releases: replays and proofs need the raw
+/// sequence to be identical on every machine and every build.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameRng {
+    state: u64,
+}
+
+impl GameRng {
+    /// Create a new generator from a 64-bit seed.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Create the generator of one stream of the game with the given seed, seeded with a
+    /// sub-seed of its own.
+    pub fn stream(seed: u64, stream: RngStream) -> Self {
+        Self::new(GameRng::new(seed ^ stream.salt()).next_u64())
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        rand::rand_core::impls::fill_bytes_via_next(self, dst)
+    }
+}
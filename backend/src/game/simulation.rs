@@ -0,0 +1,146 @@
+//! Headless self-play.
+//!
+//! Plays whole games between [`Agent`](crate::game::agents::Agent)s on the game core alone
+//! (no actors, no timers) and aggregates their results, to check the balance of a rule set
+//! before shipping it. The `simulate` binary is a command-line front end to this module.
+//!
+//! Games are determined by their seed, so a simulation run can be reproduced exactly.
+
+use std::fmt;
+
+use crate::config::game::GameRules;
+use crate::game::agents::bot_action;
+use crate::game::rng::{GameRng, RngStream};
+use crate::game::state::{GameOutcome, GameResult, GameState};
+use crate::game::types::{AgentKind, GameMode};
+use crate::server::game_session::messages::PlayerAction;
+use crate::server::matchmaking::types::{PlayerInfo, assign_teams, bot_info};
+
+/// Result of a single simulated game.
+#[derive(Debug, Clone)]
+pub struct SimulatedGame {
+    /// Outcome, or `None` if the game was stopped at the turn cap.
+    pub outcome: Option<GameOutcome>,
+    /// Number of turns played.
+    pub turns: u32,
+}
+
+/// Seat one bot of each given kind, in order, with teams assigned as matchmaking would.
+pub fn bot_infos(seats: &[AgentKind], rules: &GameRules, seed: u64) -> Vec<PlayerInfo> {
+    let mut infos: Vec<PlayerInfo> = seats.iter().enumerate()
+        .map(|(i, &kind)| bot_info(kind, i + 1))
+        .collect();
+    assign_teams(&mut infos, rules.team_count, &mut GameRng::stream(seed, RngStream::Teams));
+    infos
+}
+
+/// Play a game between the given agents until it is over or `max_turns` turns were played.
+///
+/// Player ID `i + 1` is played by `seats[i]`, from what they can see.
+pub fn play_game(rules: &GameRules, mode: GameMode, seats: &[AgentKind], seed: u64, max_turns: u32) -> SimulatedGame {
+    let mut state = GameState::new(rules.clone(), bot_infos(seats, rules, seed), mode, seed);
+    let mut outcome = state.outcome();
+    while outcome.is_none() && state.turn <= max_turns {
+        let actions: Vec<(u8, PlayerAction)> = state.players.iter()
+            .filter(|p| p.is_alive)
+            .map(|p| (p.id, bot_action(seats[p.id as usize - 1], &state, p.id)))
+            .collect();
+        state = state.step(&actions).0;
+        outcome = state.outcome();
+    }
+    SimulatedGame { outcome, turns: state.turn - 1 }
+}
+
+/// Aggregated results of the games played in one mode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModeReport {
+    /// Mode the games were played in.
+    pub mode: GameMode,
+    /// Agent of each seat, in spawn order.
+    pub seats: Vec<AgentKind>,
+    /// Number of games played.
+    pub games: u32,
+    /// Games won by each seat (alone or with their team), in spawn order.
+    pub wins: Vec<u32>,
+    /// Games ending in a draw.
+    pub draws: u32,
+    /// Games stopped at the turn cap.
+    pub unfinished: u32,
+    /// Turns played over all games.
+    pub total_turns: u64,
+}
+
+impl ModeReport {
+    /// Empty report for the given mode and seats.
+    pub fn new(mode: GameMode, seats: &[AgentKind]) -> Self {
+        Self {
+            mode,
+            seats: seats.to_vec(),
+            games: 0,
+            wins: vec![0; seats.len()],
+            draws: 0,
+            unfinished: 0,
+            total_turns: 0,
+        }
+    }
+
+    /// Add the result of one game.
+    pub fn record(&mut self, game: &SimulatedGame) {
+        self.games += 1;
+        self.total_turns += game.turns as u64;
+        let Some(outcome) = &game.outcome else {
+            self.unfinished += 1;
+            return;
+        };
+        let winners: &[u8] = match &outcome.result {
+            GameResult::Winner { player_id } => std::slice::from_ref(player_id),
+            GameResult::TeamWinner { player_ids, .. } => player_ids,
+            GameResult::Draw { .. } => {
+                self.draws += 1;
+                &[]
+            }
+        };
+        for &id in winners {
+            if let Some(wins) = self.wins.get_mut(id as usize - 1) {
+                *wins += 1;
+            }
+        }
+    }
+
+    /// Average number of turns per game.
+    pub fn average_turns(&self) -> f64 {
+        self.total_turns as f64 / self.games.max(1) as f64
+    }
+}
+
+/// Percentage of `count` over `total`.
+fn percent(count: u32, total: u32) -> f64 {
+    100.0 * count as f64 / total.max(1) as f64
+}
+
+impl fmt::Display for ModeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:?}: {} games, {:.1} turns on average, {:.1}% draws, {:.1}% unfinished",
+            self.mode,
+            self.games,
+            self.average_turns(),
+            percent(self.draws, self.games),
+            percent(self.unfinished, self.games),
+        )?;
+        for (i, (kind, wins)) in self.seats.iter().zip(&self.wins).enumerate() {
+            writeln!(f, "  seat {} ({:?}): {:.1}% wins", i + 1, kind, percent(*wins, self.games))?;
+        }
+        Ok(())
+    }
+}
+
+/// Play `games` games in `mode`, game `i` with seed `seed + i`, and aggregate their results.
+pub fn simulate_mode(rules: &GameRules, mode: GameMode, seats: &[AgentKind], games: u32, seed: u64, max_turns: u32) -> ModeReport {
+    let mut report = ModeReport::new(mode, seats);
+    for i in 0..games {
+        report.record(&play_game(rules, mode, seats, seed.wrapping_add(i as u64), max_turns));
+    }
+    report
+}
@@ -2,18 +2,70 @@
 //!
 //! This module defines the main GameState struct, which tracks the grid, players,
 //! cannonballs, turn number, and targeted tiles. It also provides methods to
-//! initialize the game, apply player actions, and advance turns.
+//! initialize the game and to resolve a turn through the pure [`GameState::step`]
+//! transition, which reports what happened as a list of [`GameEvent`]s.
+//!
+//! Once the mode's win condition is met, [`GameState::outcome`] reports the winner (or
+//! draw) and the final placements.
+//!
+//! All randomness is drawn from the state's own seeded [`GameRng`], so a game is fully
+//! determined by its seed and the sequence of actions applied to it.
 
+use std::collections::BTreeMap;
 use serde::{Serialize, Deserialize};
-use rand::{Rng, rng};
+use rand::Rng;
 
-use crate::game::types::{Player, Cell, Cannonball, TargetedTile, GameMode};
-use crate::game::grid::generate_grid;
-use crate::game::entities::{spawn_random_player, spawn_random_cannonballs, shoot_cannonball};
-use crate::game::systems::{move_player, apply_rules, apply_player_rules};
+use crate::game::types::{Player, Cell, Cannonball, TargetedTile, GameMode, Direction, Position, Item, ItemUse};
+use crate::game::rng::{GameRng, RngStream};
+use crate::config::game::GameRules;
+use crate::game::events::GameEvent;
+use crate::game::errors::ActionError;
+use crate::game::modes::mode_rules;
+use crate::game::grid::GameMap;
+use crate::game::entities::{
+    spawn_player, spawn_candidates, spawn_random_cannonballs, shoot_cannonball, legal_targets, check_target,
+    check_item_use, use_item,
+};
+use crate::game::systems::{resolve_moves, apply_rules, apply_player_rules};
 use crate::server::game_session::messages::PlayerAction;
 use crate::server::matchmaking::types::PlayerInfo;
 
+/// How a finished game ended.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "result", content = "data")]
+pub enum GameResult {
+    /// A single player survived.
+    Winner { player_id: u8 },
+    /// Nobody survived: the players eliminated during the last elimination turn share the win.
+    Draw { player_ids: Vec<u8> },
+    /// A single team survived: all of its members win, including those eliminated.
+    TeamWinner { team_id: u8, player_ids: Vec<u8> },
+}
+
+/// Final rank of a single player.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Placement {
+    /// Player ID.
+    pub player_id: u8,
+    /// Display username.
+    pub username: String,
+    /// Team of the player in team modes.
+    pub team_id: Option<u8>,
+    /// Rank starting at 1; players eliminated during the same turn share the same rank.
+    pub rank: u8,
+    /// Turn during which the player was eliminated (`None` for the winner).
+    pub eliminated_turn: Option<u32>,
+}
+
+/// Result of a finished game, with every player's placement.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameOutcome {
+    /// Winner or draw.
+    pub result: GameResult,
+    /// All players, best rank first.
+    pub placements: Vec<Placement>,
+}
+
 /// Represents the full state of a running game.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameState {
@@ -23,69 +75,263 @@ pub struct GameState {
     pub players: Vec<Player>,
     /// All cannonballs currently on the grid.
     pub cannonballs: Vec<Cannonball>,
+    /// All items currently on the grid.
+    pub items: Vec<Item>,
     /// Current turn number (starts at 1).
     pub turn: u32,
     /// Tiles targeted by cannonball shots this turn.
     pub targeted_tiles: Vec<TargetedTile>,
     /// Current game mode.
     pub mode: GameMode,
+    /// Seed the game was created with (enough to reproduce it given the same actions).
+    pub seed: u64,
+    /// Random number generator driving every random decision of this game.
+    pub rng: GameRng,
+    /// Rule set of this game.
+    pub rules: GameRules,
 }
 
 impl GameState {
-    /// Create a new game state with the given grid size, player infos, and mode.
-    pub fn new(rows: usize, cols: usize, player_infos: Vec<PlayerInfo>, mode: GameMode) -> Self {
-        // Generate the grid only once
-        let grid = generate_grid(rows, cols);
+    /// Create a new game state with the given rules, player infos, mode and seed, on an
+    /// open grid of the rules' size.
+    ///
+    /// Two states created with the same arguments are identical.
+    pub fn new(rules: GameRules, player_infos: Vec<PlayerInfo>, mode: GameMode, seed: u64) -> Self {
+        let map = GameMap::open(rules.grid_rows, rules.grid_cols);
+        Self::new_on_map(rules, player_infos, mode, &map, seed)
+    }
+
+    /// Create a new game state on the given map.
+    ///
+    /// Players spawn with the mode's [`SpawnStrategy`](crate::game::types::SpawnStrategy),
+    /// on the map's spawn pads when it has any left. The map's
+    /// cannonballs are placed as is; a map without any gets the rules' random ones.
+    pub fn new_on_map(rules: GameRules, player_infos: Vec<PlayerInfo>, mode: GameMode, map: &GameMap, seed: u64) -> Self {
+        let mut rng = GameRng::stream(seed, RngStream::State);
+
+        // Copy the map's grid, with the mode's special tiles.
+        let mut grid = map.grid.clone();
+        mode_rules(mode).prepare_grid(&mut grid);
         
         let mut players = vec![];
 
-        // Spawn each player at a random valid position.
+        // Place each player with the mode's spawn strategy. Teams only exist in team modes.
+        let team_play = mode_rules(mode).team_play();
+        let strategy = mode_rules(mode).spawn_strategy(&rules);
         for (i, info) in player_infos.iter().enumerate() {
-            if let Some(player) = spawn_random_player(&grid, &players, (i+1) as u8, info.username.clone()) {
+            let id = (i + 1) as u8;
+            if let Some(mut player) = spawn_player(&grid, rules.topology, &players, id, info.username.clone(), strategy, info.spawn_pick, &mut rng) {
+                player.team_id = info.team_id.filter(|_| team_play);
                 players.push(player);
             }
         }
 
-        // Initialize an empty cannonballs list
-        let cannonballs_list: Vec<Cannonball> = Vec::new();
-        
-        // Randomly determine the number of cannonballs to spawn (1 to 3).
-        let nb_cannonballs = rng().random_range(1..=3);
-        let cannonballs = spawn_random_cannonballs(&grid, &players, &cannonballs_list, nb_cannonballs);
+        let cannonballs = if map.cannonballs.is_empty() {
+            // Randomly determine the number of cannonballs to spawn within the rules' bounds.
+            let max_cannonballs = rules.initial_cannonballs_max.max(rules.initial_cannonballs_min);
+            let nb_cannonballs = rng.random_range(rules.initial_cannonballs_min..=max_cannonballs);
+            spawn_random_cannonballs(&grid, &players, &[], &[], nb_cannonballs, rules.topology, rules.cannonball_min_distance, &mut rng)
+        } else {
+            map.cannonballs.iter().map(|&pos| Cannonball { pos }).collect()
+        };
 
         GameState {
             grid,
             players,
             cannonballs,
+            items: Vec::new(),
             turn: 1,
             targeted_tiles: Vec::new(),
             mode,
+            seed,
+            rng,
+            rules,
         }
     }
 
-    /// Apply a player action (move or shoot) for the given player index.
-    pub fn apply_player_action(&mut self, action: PlayerAction, player_index: usize) {
+    /// Tiles a player may pick in the spawn draft on the given map: its free spawn pads, or
+    /// its solid tiles without any, once the mode has prepared the grid.
+    pub fn draft_candidates(mode: GameMode, map: &GameMap) -> Vec<Position> {
+        let mut grid = map.grid.clone();
+        mode_rules(mode).prepare_grid(&mut grid);
+        spawn_candidates(&grid, &[])
+    }
+
+    /// Resolve one full turn and return the resulting state with the events that happened.
+    ///
+    /// `actions` pairs a player ID with the action that player chose this turn. All actions
+    /// are resolved simultaneously from the start-of-turn state, so their order in the slice
+    /// does not matter: items are used first, then shots are fired, then all moves (and
+    /// dashes) are resolved together (see [`resolve_moves`] for the conflict rules), then
+    /// pickups and deaths are checked. Frozen players skip their action and thaw.
+    /// Invalid actions (see [`GameState::validate_action`]) are ignored, and only the first
+    /// valid action of each player is taken into account. End-of-turn rules (tile breaking, cannonball hits)
+    /// are then applied and the turn counter advances. `self` is left untouched, so the same
+    /// state and actions always yield the same result.
+    pub fn step(&self, actions: &[(u8, PlayerAction)]) -> (GameState, Vec<GameEvent>) {
+        let mut next = self.clone();
+        let mut events = Vec::new();
+
+        // Keep one action per living player, indexed like `players`.
+        let mut chosen: Vec<Option<&PlayerAction>> = vec![None; next.players.len()];
+        for (player_id, action) in actions {
+            if self.validate_action(*player_id, action).is_err() {
+                continue;
+            }
+            if let Some(index) = next.players.iter().position(|p| p.id == *player_id) {
+                chosen[index].get_or_insert(action);
+            }
+        }
+
+        // Frozen players skip this turn's action.
+        for (index, action) in chosen.iter_mut().enumerate() {
+            let player = &mut next.players[index];
+            if player.frozen {
+                player.frozen = false;
+                if player.is_alive {
+                    *action = None;
+                    events.push(GameEvent::ActionSkipped { player_id: player.id });
+                }
+            }
+        }
+
+        // Items are used before anything else happens.
+        for (index, action) in chosen.iter().enumerate() {
+            if let Some(PlayerAction::UseItem(item_use)) = action {
+                use_item(&mut next, index, *item_use, &mut events);
+            }
+        }
+
+        // Shots are paid with start-of-turn ammunition.
+        for (index, action) in chosen.iter().enumerate() {
+            if let Some(PlayerAction::Shoot { x, y }) = action {
+                shoot_cannonball(&mut next, index, *x, *y, &mut events);
+            }
+        }
+
+        // All moves are resolved together from start-of-turn positions.
+        let moves: Vec<(usize, Direction, usize)> = chosen.iter().enumerate()
+            .filter_map(|(index, action)| match action {
+                Some(PlayerAction::Move(direction)) => Some((index, *direction, 1)),
+                Some(PlayerAction::UseItem(ItemUse::Dash(direction))) => Some((index, *direction, 2)),
+                _ => None,
+            })
+            .collect();
+        resolve_moves(&mut next, &moves, &mut events);
+
+        // Pickups and deaths once everybody has moved.
+        for index in 0..next.players.len() {
+            apply_player_rules(&mut next, index, &mut events);
+        }
+
+        next.end_turn(&mut events);
+
+        (next, events)
+    }
+
+    /// Check whether the player with the given ID may take `action` in this state.
+    ///
+    /// Moves must exist on the board's topology, stay on the grid and not run into a wall (moving onto a broken tile is
+    /// allowed, if fatal). Items must be held (see [`check_item_use`]). Shots
+    /// need a cannonball and a legal target: on the grid, not broken, within range and, if
+    /// required, in line of sight. Shots at tiles other players chose this turn are checked
+    /// by [`GameState::validate_turn_action`].
+    pub fn validate_action(&self, player_id: u8, action: &PlayerAction) -> Result<(), ActionError> {
+        let index = self.players.iter().position(|p| p.id == player_id).ok_or(ActionError::UnknownPlayer)?;
+        let player = &self.players[index];
+        if !player.is_alive {
+            return Err(ActionError::PlayerEliminated);
+        }
+        if !mode_rules(self.mode).allows_action(action) {
+            return Err(ActionError::NotAllowedInMode);
+        }
+
         match action {
-            PlayerAction::Move(direction) => {
-                // Move the player in the specified direction.
-                move_player(self, player_index, direction);
+            PlayerAction::Move(direction) => self.check_move(player.pos, *direction),
+            PlayerAction::UseItem(item_use) => {
+                check_item_use(self, index, *item_use)?;
+                match item_use {
+                    ItemUse::Dash(direction) => self.check_move(player.pos, *direction),
+                    _ => Ok(()),
+                }
             }
             PlayerAction::Shoot { x, y } => {
-                // Attempt to shoot a cannonball at the specified tile.
-                shoot_cannonball(self, player_index, x, y);
+                if player.cannonball_count == 0 {
+                    return Err(ActionError::NoAmmo);
+                }
+                check_target(self, index, Position { x: *x, y: *y })
             }
         }
-        // Apply rules that affect only this player (e.g., pickup, death).
-        apply_player_rules(self, player_index);
     }
 
-    /// Advance to the next turn, applying global and per-player rules.
-    pub fn next_turn(&mut self) {
+    /// Check an action submitted during a turn like [`GameState::validate_action`], given
+    /// the actions `others` other players already submitted (pairs of player ID and
+    /// action): a shot at a tile another player is shooting at is rejected with
+    /// [`ActionError::AlreadyTargeted`]. Actions of frozen players are skipped, so their
+    /// shots target nothing.
+    ///
+    /// [`GameState::step`] does not apply this check: shots given to it at the same tile
+    /// are all fired, whatever their order.
+    pub fn validate_turn_action<'a>(
+        &self,
+        player_id: u8,
+        action: &PlayerAction,
+        others: impl IntoIterator<Item = (u8, &'a PlayerAction)>,
+    ) -> Result<(), ActionError> {
+        self.validate_action(player_id, action)?;
+        if let PlayerAction::Shoot { x, y } = action {
+            let taken = others.into_iter().any(|(other_id, other)| {
+                other_id != player_id
+                    && matches!(other, PlayerAction::Shoot { x: ox, y: oy } if ox == x && oy == y)
+                    && self.players.iter().any(|p| p.id == other_id && !p.frozen)
+            });
+            if taken {
+                return Err(ActionError::AlreadyTargeted);
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that a move from `pos` in the given direction exists on the board's topology,
+    /// neither leaves the grid nor runs into a wall.
+    fn check_move(&self, pos: Position, direction: Direction) -> Result<(), ActionError> {
+        let topology = self.rules.topology;
+        if !topology.allows(direction) {
+            return Err(ActionError::InvalidDirection);
+        }
+        let rows = self.grid.len();
+        let cols = self.grid.first().map_or(0, |row| row.len());
+        let next = topology.neighbour(pos, direction, rows, cols).ok_or(ActionError::OutOfBounds)?;
+        if !self.grid[next.y][next.x].is_walkable() {
+            return Err(ActionError::Blocked);
+        }
+        Ok(())
+    }
+
+    /// Return the outcome of the game if it is over, following the mode's win condition
+    /// (by default, see [`last_player_standing`](crate::game::modes::last_player_standing)).
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        mode_rules(self.mode).outcome(self)
+    }
+
+    /// Legal shot targets of every living player, keyed by player ID.
+    ///
+    /// Players without cannonballs have no target. Clients use this to highlight the tiles
+    /// a player may shoot at this turn.
+    pub fn shot_targets(&self) -> BTreeMap<u8, Vec<Position>> {
+        self.players.iter().enumerate()
+            .filter(|(_, p)| p.is_alive)
+            .map(|(i, p)| (p.id, legal_targets(self, i)))
+            .collect()
+    }
+
+    /// Finish the turn, applying global and per-player rules.
+    fn end_turn(&mut self, events: &mut Vec<GameEvent>) {
         // Apply global rules (e.g., break a tile, resolve cannonball hits).
-        apply_rules(self);
+        apply_rules(self, events);
         // Apply per-player rules (e.g., check for death, pickup).
         for i in 0..self.players.len() {
-            apply_player_rules(self, i);
+            apply_player_rules(self, i, events);
         }
         self.turn += 1;
     }
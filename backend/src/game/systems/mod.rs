@@ -4,7 +4,8 @@
 
 pub mod movement;
 pub mod rules;
+pub mod render;
 
 pub use movement::*;
 pub use rules::*;
-// pub use render::*;
\ No newline at end of file
+pub use render::*;
\ No newline at end of file
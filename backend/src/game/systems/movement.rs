@@ -1,34 +1,148 @@
 //! Player movement system.
 //!
-//! This module handles moving players on the grid.
+//! This module handles moving players on the grid. All moves of a turn are resolved
+//! simultaneously from the start-of-turn positions, so the order in which actions
+//! were received never matters:
+//!
+//! - Two or more players moving onto the same tile all bounce back and stay where they were.
+//! - Two players moving onto each other's tile (head-on swap) both bounce back.
+//! - A player moving onto a tile whose occupant does not leave it bounces back.
+//!
+//! Bounces can cascade (a player following a bounced player bounces too). Moving into a
+//! tile vacated during the same turn, including rotations of three or more players, is allowed.
+//!
+//! Which tiles a direction leads to depends on the rules' [`Topology`] (four or eight
+//! directions on square tiles, six on hexagons).
+//!
+//! A dash covers two tiles instead of one, stopping early at a wall, the grid edge or a
+//! teleporter. Special tiles shape where a move ends: walls cannot be entered, ice keeps a player
+//! sliding until they reach a non-ice tile, and a teleporter sends a player ending their
+//! move on it to its paired teleporter. Conflicts are checked on the final tiles only.
 
-use crate::game::types::{Direction, Position};
+use crate::game::types::{Cell, Direction, Position};
+use crate::game::topology::Topology;
 use crate::game::state::GameState;
+use crate::game::events::GameEvent;
 
-/// Move the specified player in the given direction.
-/// Returns the new position.
-pub fn move_player(game_state: &mut GameState, player_index: usize, direction: Direction) -> Position {
-    let player = &mut game_state.players[player_index];
-    let mut new_pos = player.pos;
+/// Compute the tile where a move from `pos` in the given direction ends, before teleporting.
+///
+/// The move is cancelled by a wall, the grid edge or a direction the topology lacks. Once on ice, the player keeps sliding
+/// until they reach a non-ice tile, or stops on the last ice tile before a wall or the edge.
+pub fn slide_destination(grid: &[Vec<Cell>], topology: Topology, pos: Position, direction: Direction) -> Position {
+    let grid_height = grid.len();
+    let grid_width = grid.first().map_or(0, |row| row.len());
 
-    match direction {
-        Direction::Up => {
-            if new_pos.y > 0 { new_pos.y -= 1; }
+    let mut current = pos;
+    loop {
+        let Some(next) = topology.neighbour(current, direction, grid_height, grid_width) else {
+            return current;
+        };
+        if next == current || !grid[next.y][next.x].is_walkable() {
+            return current;
         }
-        Direction::Down => {
-            if new_pos.y < game_state.grid.len() - 1 { new_pos.y += 1; }
+        current = next;
+        if grid[current.y][current.x] != Cell::Ice {
+            return current;
         }
-        Direction::Left => {
-            if new_pos.x > 0 { new_pos.x -= 1; }
+    }
+}
+
+/// Compute where a move of `steps` tiles from `pos` ends, before teleporting.
+///
+/// Each tile is a [`slide_destination`] step; the move stops early when blocked or on a
+/// teleporter.
+pub fn move_destination(grid: &[Vec<Cell>], topology: Topology, pos: Position, direction: Direction, steps: usize) -> Position {
+    let mut current = pos;
+    for _ in 0..steps {
+        let next = slide_destination(grid, topology, current, direction);
+        if next == current {
+            break;
         }
-        Direction::Right => {
-            if new_pos.x < game_state.grid[0].len() - 1 { new_pos.x += 1; }
+        current = next;
+        if matches!(grid[current.y][current.x], Cell::Teleporter { .. }) {
+            break;
         }
-        Direction::Stay => {
-            // No movement.
+    }
+    current
+}
+
+/// Return the exit of the teleporter at `pos`: the other tile of the same channel, if any.
+pub fn teleport_exit(grid: &[Vec<Cell>], pos: Position) -> Option<Position> {
+    let Cell::Teleporter { channel } = grid[pos.y][pos.x] else {
+        return None;
+    };
+    grid.iter().enumerate()
+        .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, cell)| (Position { x, y }, *cell)))
+        .find(|(other, cell)| *other != pos && *cell == Cell::Teleporter { channel })
+        .map(|(other, _)| other)
+}
+
+/// Resolve the moves of all living players simultaneously.
+///
+/// `moves` gives a player index, the direction chosen this turn and the number of tiles
+/// to cover (1 for a regular move, 2 for a dash); living players without an entry stay in
+/// place. Records a `PlayerMoved` event for every player who
+/// moved (followed by `PlayerTeleported` when they took a teleporter) and a
+/// `PlayerBounced` event for every player whose move was blocked by another player.
+pub fn resolve_moves(game_state: &mut GameState, moves: &[(usize, Direction, usize)], events: &mut Vec<GameEvent>) {
+    // Only living players take part in the resolution (and can block others).
+    let movers: Vec<usize> = (0..game_state.players.len())
+        .filter(|&i| game_state.players[i].is_alive)
+        .collect();
+    let start: Vec<Position> = movers.iter().map(|&i| game_state.players[i].pos).collect();
+    // Tile where each move lands, and where it finally ends after a teleporter.
+    let landing: Vec<Position> = movers.iter().enumerate()
+        .map(|(k, &i)| {
+            let (direction, steps) = moves.iter()
+                .find(|(index, _, _)| *index == i)
+                .map_or((Direction::Stay, 0), |(_, d, s)| (*d, *s));
+            move_destination(&game_state.grid, game_state.rules.topology, start[k], direction, steps)
+        })
+        .collect();
+    let wanted: Vec<Position> = (0..movers.len())
+        .map(|k| {
+            if landing[k] == start[k] {
+                return start[k];
+            }
+            teleport_exit(&game_state.grid, landing[k]).unwrap_or(landing[k])
+        })
+        .collect();
+    let mut dest = wanted.clone();
+
+    // Bounce conflicting moves back until the destinations are stable.
+    loop {
+        // Decide every bounce of this pass from the same snapshot, so that all players
+        // involved in a conflict bounce together.
+        let bounces: Vec<usize> = (0..movers.len())
+            .filter(|&k| dest[k] != start[k])
+            .filter(|&k| {
+                // Contested tile: someone else ends up on the same tile.
+                let contested = (0..movers.len()).any(|j| j != k && dest[j] == dest[k]);
+                // Head-on swap, or the occupant of the target tile is not leaving it.
+                let blocked = (0..movers.len()).any(|j| {
+                    j != k && start[j] == dest[k] && (dest[j] == start[k] || dest[j] == start[j])
+                });
+                contested || blocked
+            })
+            .collect();
+        if bounces.is_empty() {
+            break;
+        }
+        for k in bounces {
+            dest[k] = start[k];
         }
     }
 
-    player.pos = new_pos;
-    new_pos
-}
\ No newline at end of file
+    for (k, &i) in movers.iter().enumerate() {
+        let player = &mut game_state.players[i];
+        if dest[k] != start[k] {
+            events.push(GameEvent::PlayerMoved { player_id: player.id, from: Some(start[k]), to: landing[k] });
+            if landing[k] != dest[k] {
+                events.push(GameEvent::PlayerTeleported { player_id: player.id, from: Some(landing[k]), to: dest[k] });
+            }
+            player.pos = dest[k];
+        } else if wanted[k] != start[k] {
+            events.push(GameEvent::PlayerBounced { player_id: player.id, pos: start[k], toward: wanted[k] });
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Text rendering of the grid.
+//!
+//! Draws a game state as plain text, one line per row, for logs and debugging. Each tile
+//! takes two characters; rows are indented following the board's
+//! [`Topology`](crate::game::topology::Topology) (odd rows shift by half a tile on hexagons).
+
+use crate::game::types::{Cell, Position};
+use crate::game::state::GameState;
+
+/// Render the grid with its players (`P` and their ID), cannonballs (`o`) and items (`*`).
+///
+/// Tiles use the map file legend (see [`crate::game::grid::map`]).
+pub fn render_grid(state: &GameState) -> String {
+    let topology = state.rules.topology;
+    let mut out = String::new();
+    for (y, row) in state.grid.iter().enumerate() {
+        out.push_str(&" ".repeat(topology.row_indent(y)));
+        for (x, cell) in row.iter().enumerate() {
+            let pos = Position { x, y };
+            let tile = if let Some(player) = state.players.iter().find(|p| p.is_alive && p.pos == pos) {
+                format!("P{}", player.id)
+            } else if state.cannonballs.iter().any(|c| c.pos == pos) {
+                "o".to_string()
+            } else if state.items.iter().any(|i| i.pos == pos) {
+                "*".to_string()
+            } else {
+                cell_glyph(*cell).to_string()
+            };
+            out.push_str(&format!("{:<2}", tile));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// One-character representation of a cell.
+fn cell_glyph(cell: Cell) -> char {
+    match cell {
+        Cell::Solid => '.',
+        Cell::Cracked => 'c',
+        Cell::Broken => 'x',
+        Cell::Ice => 'i',
+        Cell::Wall => '#',
+        Cell::Teleporter { channel } => char::from_digit(channel as u32, 10).unwrap_or('T'),
+        Cell::Hill => 'H',
+        Cell::SpawnPad => 'S',
+    }
+}
@@ -4,36 +4,55 @@
 
 use crate::game::types::Cell;
 use crate::game::state::GameState;
+use crate::game::events::GameEvent;
 use crate::game::grid::break_tile;
 use crate::game::utils::resolve_cannonball_hits;
+use crate::game::entities::{respawn_cannonballs, respawn_items, pick_up_item};
 
 /// Apply rules that affect a single player (e.g., pickup, death).
-pub fn apply_player_rules(game_state: &mut GameState, player_index: usize) {
+///
+/// A shielded player falling on a broken tile survives: the shield is spent and the tile
+/// under them becomes solid again. Eliminated players are left untouched.
+pub fn apply_player_rules(game_state: &mut GameState, player_index: usize, events: &mut Vec<GameEvent>) {
     let player = &mut game_state.players[player_index];
+    if !player.is_alive {
+        return;
+    }
 
     // If the player is on a cannonball, pick it up.
     if let Some(pos) = game_state.cannonballs.iter().position(|c| c.pos == player.pos) {
         player.cannonball_count += 1;
         game_state.cannonballs.remove(pos);
+        events.push(GameEvent::CannonballPickedUp { player_id: player.id, pos: player.pos });
     }
+    // Same for an item.
+    pick_up_item(game_state, player_index, events);
+    let player = &mut game_state.players[player_index];
 
     // Check grid bounds before accessing the cell.
     let grid_height = game_state.grid.len();
     let grid_width = if grid_height > 0 { game_state.grid[0].len() } else { 0 };
 
-    if player.pos.y < grid_height && player.pos.x < grid_width {
-        // If the player is on a broken tile, they die.
-        if game_state.grid[player.pos.y][player.pos.x] == Cell::Broken {
-            player.is_alive = false;
-        }
-    } else {
-        // If the player is out of bounds, they die.
+    // A player on a broken tile or out of bounds dies, unless a shield absorbs the fall.
+    let in_bounds = player.pos.y < grid_height && player.pos.x < grid_width;
+    if in_bounds && player.shielded && game_state.grid[player.pos.y][player.pos.x] == Cell::Broken {
+        player.shielded = false;
+        game_state.grid[player.pos.y][player.pos.x] = Cell::Solid;
+        events.push(GameEvent::ShieldAbsorbed { player_id: player.id, pos: player.pos });
+        return;
+    }
+    if !in_bounds || game_state.grid[player.pos.y][player.pos.x] == Cell::Broken {
         player.is_alive = false;
+        player.eliminated_turn = Some(game_state.turn);
+        events.push(GameEvent::PlayerEliminated { player_id: player.id, pos: player.pos });
     }
 }
 
-/// Apply global rules at the end of the turn (e.g., break a tile, resolve cannonball hits).
-pub fn apply_rules(game_state: &mut GameState) {
-    break_tile(game_state); // TODO remettre (juste debugging)
-    resolve_cannonball_hits(game_state);
+/// Apply global rules at the end of the turn (e.g., break a tile, resolve cannonball hits,
+/// respawn cannonballs and items).
+pub fn apply_rules(game_state: &mut GameState, events: &mut Vec<GameEvent>) {
+    break_tile(game_state, events);
+    resolve_cannonball_hits(game_state, events);
+    respawn_cannonballs(game_state, events);
+    respawn_items(game_state, events);
 }
\ No newline at end of file
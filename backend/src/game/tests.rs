@@ -1,99 +1,1882 @@
 //! Unit tests for core game logic and systems.
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::{grid::*, player::*, cannonball::*, types::*};
-
-    #[test]
-    fn test_grid_generation_size() {
-        let grid = create_grid(10, 10);
-        assert_eq!(grid.len(), 10);
-        assert!(grid.iter().all(|row| row.len() == 10));
-    }
-
-    #[test]
-    fn test_player_spawn_no_overlap() {
-        let grid = create_grid(10, 10);
-        let mut players = vec![];
-
-        for id in 0..5 {
-            let player = spawn_random_player(&grid, &players, id).expect("Failed to spawn player");
-            // Ensure no two players spawn on the same tile.
-            assert!(!players.iter().any(|p| p.pos == player.pos));
-            players.push(player);
+use crate::game::grid::*;
+use crate::game::entities::*;
+use crate::game::systems::*;
+use crate::game::types::*;
+use crate::game::utils::blast_tiles;
+use crate::game::modes::{ModeRules, MODES, available_modes, mode_rules};
+use crate::game::modes::shrinking_ring::ring_index;
+use crate::game::state::{GameState, GameResult, GameOutcome};
+use crate::game::simulation::{ModeReport, SimulatedGame, bot_infos, play_game};
+use crate::game::replay::Replay;
+use crate::game::rng::{GameRng, RngStream};
+use crate::game::topology::Topology;
+use crate::config::game::{GameRules, SpawnSchedule};
+use crate::cli::{parse_list, parse_name};
+use crate::game::events::GameEvent;
+use crate::game::errors::{ActionError, MapError, ReplayError};
+use crate::game::view::Vision;
+use crate::game::agents::{AGENTS, agent, bot_action, legal_actions};
+use crate::server::game_session::messages::PlayerAction;
+use crate::server::matchmaking::types::{PlayerInfo, assign_teams};
+
+/// Build `count` player infos with distinct wallets and usernames.
+fn player_infos(count: usize) -> Vec<PlayerInfo> {
+    (1..=count)
+        .map(|i| PlayerInfo {
+            id: format!("0x{}", i),
+            username: format!("player{}", i),
+            team_id: None,
+            spawn_pick: None,
+            bot: None,
+        })
+        .collect()
+}
+
+/// Build `team_ids.len()` player infos like [`player_infos`], each in the given team.
+fn team_infos(team_ids: &[u8]) -> Vec<PlayerInfo> {
+    let mut infos = player_infos(team_ids.len());
+    for (info, team_id) in infos.iter_mut().zip(team_ids) {
+        info.team_id = Some(*team_id);
+    }
+    infos
+}
+
+/// Build a game of the given mode on a clean 5x5 grid with the default rules and no
+/// cannonballs, with the players of `infos` standing on `positions` (as `(x, y)`).
+fn game(mode: GameMode, infos: Vec<PlayerInfo>, positions: &[(usize, usize)]) -> GameState {
+    let mut state = GameState::new(GameRules::default(), infos, mode, 7);
+    assert_eq!(state.players.len(), positions.len());
+    state.cannonballs.clear();
+    for (player, &(x, y)) in state.players.iter_mut().zip(positions) {
+        player.pos = Position { x, y };
+    }
+    state
+}
+
+/// Serialize a game state, used to compare two states for equality.
+fn snapshot(state: &GameState) -> String {
+    serde_json::to_string(state).expect("GameState should serialize")
+}
+
+#[test]
+fn test_grid_generation_size() {
+    let grid = generate_grid(10, 10);
+    assert_eq!(grid.len(), 10);
+    assert!(grid.iter().all(|row| row.len() == 10));
+}
+
+#[test]
+fn test_player_spawn_no_overlap() {
+    let grid = generate_grid(10, 10);
+    let mut rng = GameRng::new(42);
+    let mut players = vec![];
+
+    for id in 0..5 {
+        let player = spawn_random_player(&grid, &players, id, format!("player{}", id), &mut rng)
+            .expect("Failed to spawn player");
+        // Ensure no two players spawn on the same tile.
+        assert!(!players.iter().any(|p: &Player| p.pos == player.pos));
+        players.push(player);
+    }
+}
+
+#[test]
+fn test_spawn_player_no_space() {
+    let grid = vec![vec![Cell::Broken; 5]; 5]; // No valid tiles.
+    let mut rng = GameRng::new(42);
+    let player = spawn_random_player(&grid, &[], 0, "player0".to_string(), &mut rng);
+    assert!(player.is_none());
+}
+
+#[test]
+fn test_cannonball_spawn_limit() {
+    let grid = generate_grid(5, 5);
+    let mut rng = GameRng::new(42);
+    let cannonballs = spawn_random_cannonballs(&grid, &[], &[], &[], 100, Topology::Square, 0, &mut rng);
+    // There should never be more cannonballs than solid tiles.
+    assert!(cannonballs.len() <= 25);
+}
+
+#[test]
+fn test_move_player_into_lava() {
+    let mut state = GameState::new(GameRules::default(), player_infos(1), GameMode::Classic, 42);
+    state.cannonballs.clear();
+    state.players[0].pos = Position { x: 2, y: 2 };
+    state.grid[2][3] = Cell::Broken;
+
+    let mut events = vec![];
+    resolve_moves(&mut state, &[(0, Direction::Right, 1)], &mut events);
+    apply_player_rules(&mut state, 0, &mut events);
+
+    // Player should die if moving into lava.
+    assert!(!state.players[0].is_alive);
+}
+
+#[test]
+fn test_pickup_cannonball() {
+    let mut state = GameState::new(GameRules::default(), player_infos(1), GameMode::Classic, 42);
+    state.players[0].pos = Position { x: 2, y: 2 };
+    state.cannonballs = vec![Cannonball { pos: Position { x: 3, y: 2 } }];
+
+    let mut events = vec![];
+    resolve_moves(&mut state, &[(0, Direction::Right, 1)], &mut events);
+    apply_player_rules(&mut state, 0, &mut events);
+
+    // Player should pick up the cannonball.
+    assert_eq!(state.players[0].cannonball_count, 1);
+    assert!(state.cannonballs.is_empty());
+}
+
+#[test]
+fn test_break_tile_replaces_with_lava() {
+    let mut state = GameState::new(GameRules::default(), player_infos(1), GameMode::Classic, 42);
+
+    break_tile(&mut state, &mut vec![]);
+    let lava_count = state.grid.iter().flatten().filter(|&&c| c == Cell::Broken).count();
+    assert_eq!(lava_count, 1);
+}
+
+#[test]
+fn test_player_does_not_spawn_on_object() {
+    for seed in 0..50 {
+        let state = GameState::new(GameRules::default(), player_infos(3), GameMode::Classic, seed);
+        for p in &state.players {
+            // Player should not spawn on a cannonball.
+            assert!(!state.cannonballs.iter().any(|c| c.pos == p.pos));
+        }
+    }
+}
+
+#[test]
+fn test_same_seed_creates_identical_games() {
+    let a = GameState::new(GameRules::default(), player_infos(3), GameMode::Cracked, 1234);
+    let b = GameState::new(GameRules::default(), player_infos(3), GameMode::Cracked, 1234);
+    assert_eq!(snapshot(&a), snapshot(&b));
+    assert_eq!(a.seed, 1234);
+}
+
+#[test]
+fn test_different_seeds_create_different_games() {
+    let reference = snapshot(&GameState::new(GameRules::default(), player_infos(3), GameMode::Classic, 0));
+    // With 25 tiles and three players, at least one of these seeds must differ.
+    assert!((1..20).any(|seed| {
+        snapshot(&GameState::new(GameRules::default(), player_infos(3), GameMode::Classic, seed)) != reference
+    }));
+}
+
+#[test]
+fn test_rng_streams_of_a_seed_are_unrelated() {
+    use rand::RngCore;
+
+    let streams = [RngStream::State, RngStream::ModeChoice, RngStream::Map, RngStream::Teams];
+    let firsts: Vec<u64> = streams.iter().map(|&s| GameRng::stream(7, s).next_u64()).collect();
+    for (i, a) in firsts.iter().enumerate() {
+        assert!(firsts[i + 1..].iter().all(|b| a != b));
+        assert_ne!(*a, GameRng::new(7).next_u64());
+    }
+    assert_eq!(GameRng::stream(7, RngStream::Map), GameRng::stream(7, RngStream::Map));
+}
+
+#[test]
+fn test_same_seed_and_actions_replay_identically() {
+    let actions = [
+        PlayerAction::Move(Direction::Up),
+        PlayerAction::Move(Direction::Left),
+        PlayerAction::Shoot { x: 0, y: 0 },
+        PlayerAction::Move(Direction::Stay),
+    ];
+    let play = || {
+        let mut state = GameState::new(GameRules::default(), player_infos(2), GameMode::Cracked, 99);
+        let mut log = vec![];
+        for action in &actions {
+            let turn: Vec<(u8, PlayerAction)> = state.players.iter().map(|p| (p.id, action.clone())).collect();
+            let (next, events) = state.step(&turn);
+            state = next;
+            log.extend(events);
+        }
+        (state, log)
+    };
+    let (state_a, log_a) = play();
+    let (state_b, log_b) = play();
+    assert_eq!(snapshot(&state_a), snapshot(&state_b));
+    assert_eq!(log_a, log_b);
+}
+
+#[test]
+fn test_step_does_not_mutate_input_state() {
+    let state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    let before = snapshot(&state);
+    let (next, _) = state.step(&[(1, PlayerAction::Move(Direction::Right))]);
+    assert_eq!(snapshot(&state), before);
+    assert_eq!(next.turn, state.turn + 1);
+    assert_eq!(next.players[0].pos, Position { x: 1, y: 0 });
+}
+
+#[test]
+fn test_step_reports_moves_and_pickups() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.cannonballs = vec![Cannonball { pos: Position { x: 1, y: 0 } }];
+    let (next, events) = state.step(&[
+        (1, PlayerAction::Move(Direction::Right)),
+        (2, PlayerAction::Move(Direction::Stay)),
+    ]);
+
+    assert_eq!(events[0], GameEvent::PlayerMoved {
+        player_id: 1,
+        from: Some(Position { x: 0, y: 0 }),
+        to: Position { x: 1, y: 0 },
+    });
+    assert_eq!(events[1], GameEvent::CannonballPickedUp { player_id: 1, pos: Position { x: 1, y: 0 } });
+    // Staying in place is not a move.
+    assert!(!events.iter().any(|e| matches!(e, GameEvent::PlayerMoved { player_id: 2, .. })));
+    // Classic mode breaks exactly one tile at the end of the turn.
+    assert_eq!(events.iter().filter(|e| matches!(e, GameEvent::TileBroken { .. })).count(), 1);
+    assert_eq!(next.players[0].cannonball_count, 1);
+}
+
+#[test]
+fn test_step_reports_shot_and_elimination() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.players[0].cannonball_count = 1;
+    let (next, events) = state.step(&[
+        (1, PlayerAction::Shoot { x: 4, y: 4 }),
+        (2, PlayerAction::Move(Direction::Stay)),
+    ]);
+
+    let target = Position { x: 4, y: 4 };
+    assert!(events.contains(&GameEvent::ShotFired { player_id: 1, target }));
+    assert!(events.contains(&GameEvent::TileBroken { pos: target }));
+    assert!(events.contains(&GameEvent::PlayerEliminated { player_id: 2, pos: target }));
+    assert!(!next.players[1].is_alive);
+
+    // An eliminated player is only reported once.
+    let (_, events) = next.step(&[(1, PlayerAction::Move(Direction::Stay))]);
+    assert!(!events.iter().any(|e| matches!(e, GameEvent::PlayerEliminated { .. })));
+}
+
+#[test]
+fn test_step_ignores_actions_of_eliminated_players() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.players[1].is_alive = false;
+    let (next, events) = state.step(&[(2, PlayerAction::Move(Direction::Up))]);
+    assert_eq!(next.players[1].pos, Position { x: 4, y: 4 });
+    assert!(!events.iter().any(|e| matches!(e, GameEvent::PlayerMoved { .. })));
+}
+
+#[test]
+fn test_head_on_swap_bounces_both_players() {
+    let state = game(GameMode::Classic, player_infos(2), &[(0, 0), (1, 0)]);
+    let (next, events) = state.step(&[
+        (1, PlayerAction::Move(Direction::Right)),
+        (2, PlayerAction::Move(Direction::Left)),
+    ]);
+
+    assert_eq!(next.players[0].pos, Position { x: 0, y: 0 });
+    assert_eq!(next.players[1].pos, Position { x: 1, y: 0 });
+    assert!(events.contains(&GameEvent::PlayerBounced {
+        player_id: 1,
+        pos: Position { x: 0, y: 0 },
+        toward: Position { x: 1, y: 0 },
+    }));
+    assert!(events.contains(&GameEvent::PlayerBounced {
+        player_id: 2,
+        pos: Position { x: 1, y: 0 },
+        toward: Position { x: 0, y: 0 },
+    }));
+}
+
+#[test]
+fn test_contested_tile_bounces_everyone_and_keeps_cannonball() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (2, 0)]);
+    state.cannonballs = vec![Cannonball { pos: Position { x: 1, y: 0 } }];
+    let (next, events) = state.step(&[
+        (1, PlayerAction::Move(Direction::Right)),
+        (2, PlayerAction::Move(Direction::Left)),
+    ]);
+
+    assert_eq!(next.players[0].pos, Position { x: 0, y: 0 });
+    assert_eq!(next.players[1].pos, Position { x: 2, y: 0 });
+    assert!(!events.iter().any(|e| matches!(e, GameEvent::CannonballPickedUp { .. })));
+    assert!(next.players.iter().all(|p| p.cannonball_count == 0));
+}
+
+#[test]
+fn test_following_a_moving_player_is_allowed() {
+    let state = game(GameMode::Classic, player_infos(3), &[(0, 0), (1, 0), (4, 4)]);
+    let (next, _) = state.step(&[
+        (1, PlayerAction::Move(Direction::Right)),
+        (2, PlayerAction::Move(Direction::Right)),
+        (3, PlayerAction::Move(Direction::Stay)),
+    ]);
+    assert_eq!(next.players[0].pos, Position { x: 1, y: 0 });
+    assert_eq!(next.players[1].pos, Position { x: 2, y: 0 });
+}
+
+#[test]
+fn test_bounces_cascade_to_followers() {
+    // Player 2 is blocked by player 3, so player 1 following player 2 is blocked too.
+    let state = game(GameMode::Classic, player_infos(3), &[(0, 0), (1, 0), (2, 0)]);
+    let (next, events) = state.step(&[
+        (1, PlayerAction::Move(Direction::Right)),
+        (2, PlayerAction::Move(Direction::Right)),
+        (3, PlayerAction::Move(Direction::Stay)),
+    ]);
+    assert_eq!(next.players[0].pos, Position { x: 0, y: 0 });
+    assert_eq!(next.players[1].pos, Position { x: 1, y: 0 });
+    assert_eq!(events.iter().filter(|e| matches!(e, GameEvent::PlayerBounced { .. })).count(), 2);
+}
+
+#[test]
+fn test_step_is_independent_of_action_order() {
+    let mut state = game(GameMode::Classic, player_infos(3), &[(0, 0), (2, 0), (1, 1)]);
+    state.players[2].cannonball_count = 1;
+    let actions = [
+        (1, PlayerAction::Move(Direction::Right)),
+        (2, PlayerAction::Move(Direction::Left)),
+        (3, PlayerAction::Shoot { x: 1, y: 0 }),
+    ];
+    let mut reversed = actions.clone();
+    reversed.reverse();
+
+    let (a, events_a) = state.step(&actions);
+    let (b, events_b) = state.step(&reversed);
+    assert_eq!(snapshot(&a), snapshot(&b));
+    assert_eq!(events_a, events_b);
+}
+
+#[test]
+fn test_simultaneous_shots_on_same_tile_both_spend_ammo() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.players[0].cannonball_count = 1;
+    state.players[1].cannonball_count = 1;
+    let (next, events) = state.step(&[
+        (1, PlayerAction::Shoot { x: 2, y: 2 }),
+        (2, PlayerAction::Shoot { x: 2, y: 2 }),
+    ]);
+    assert!(next.players.iter().all(|p| p.cannonball_count == 0));
+    assert_eq!(events.iter().filter(|e| matches!(e, GameEvent::ShotFired { .. })).count(), 2);
+    assert_eq!(next.grid[2][2], Cell::Broken);
+}
+
+#[test]
+fn test_outcome_is_none_while_several_players_alive() {
+    assert!(game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]).outcome().is_none());
+}
+
+#[test]
+fn test_outcome_reports_winner_and_placements() {
+    let mut state = game(GameMode::Classic, player_infos(3), &[(0, 0), (2, 2), (4, 4)]);
+    state.players[0].is_alive = false;
+    state.players[0].eliminated_turn = Some(2);
+    state.players[2].is_alive = false;
+    state.players[2].eliminated_turn = Some(5);
+
+    let outcome = state.outcome().expect("game should be over");
+    assert_eq!(outcome.result, GameResult::Winner { player_id: 2 });
+    let order: Vec<(u8, u8)> = outcome.placements.iter().map(|p| (p.player_id, p.rank)).collect();
+    assert_eq!(order, vec![(2, 1), (3, 2), (1, 3)]);
+    assert_eq!(outcome.placements[0].eliminated_turn, None);
+}
+
+#[test]
+fn test_outcome_is_a_draw_when_last_players_die_together() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.players[0].cannonball_count = 1;
+    state.players[1].cannonball_count = 1;
+    let (next, _) = state.step(&[
+        (1, PlayerAction::Shoot { x: 4, y: 4 }),
+        (2, PlayerAction::Shoot { x: 0, y: 0 }),
+    ]);
+
+    let outcome = next.outcome().expect("game should be over");
+    assert_eq!(outcome.result, GameResult::Draw { player_ids: vec![1, 2] });
+    assert!(outcome.placements.iter().all(|p| p.rank == 1 && p.eliminated_turn == Some(state.turn)));
+}
+
+#[test]
+fn test_rules_drive_grid_size_and_initial_cannonballs() {
+    let rules = GameRules {
+        grid_rows: 3,
+        grid_cols: 8,
+        initial_cannonballs_min: 4,
+        initial_cannonballs_max: 4,
+        ..GameRules::default()
+    };
+    let state = GameState::new(rules.clone(), player_infos(3), GameMode::Classic, 5);
+    assert_eq!(state.grid.len(), 3);
+    assert!(state.grid.iter().all(|row| row.len() == 8));
+    assert_eq!(state.cannonballs.len(), 4);
+    assert_eq!(state.rules, rules);
+}
+
+#[test]
+fn test_spawns_stay_inside_non_square_grid() {
+    let rules = GameRules { grid_rows: 3, grid_cols: 8, ..GameRules::default() };
+    for seed in 0..50 {
+        let state = GameState::new(rules.clone(), player_infos(3), GameMode::Classic, seed);
+        let positions = state.players.iter().map(|p| p.pos).chain(state.cannonballs.iter().map(|c| c.pos));
+        for pos in positions {
+            assert!(pos.x < 8 && pos.y < 3, "{:?} is outside the grid", pos);
+        }
+    }
+}
+
+#[test]
+fn test_rules_drive_tiles_broken_per_turn() {
+    let rules = GameRules { tiles_broken_per_turn: 3, ..GameRules::default() };
+    let mut state = GameState::new(rules, player_infos(1), GameMode::Classic, 5);
+    let mut events = vec![];
+    break_tile(&mut state, &mut events);
+    assert_eq!(state.grid.iter().flatten().filter(|&&c| c == Cell::Broken).count(), 3);
+    assert_eq!(events.iter().filter(|e| matches!(e, GameEvent::TileBroken { .. })).count(), 3);
+}
+
+/// Run `turns` turns of a duel where nobody acts, returning the spawned cannonball count per turn.
+fn spawns_per_turn(mut state: GameState, turns: usize) -> Vec<usize> {
+    (0..turns)
+        .map(|_| {
+            let (next, events) = state.step(&[]);
+            state = next;
+            events.iter().filter(|e| matches!(e, GameEvent::CannonballSpawned { .. })).count()
+        })
+        .collect()
+}
+
+#[test]
+fn test_cannonballs_respawn_every_n_turns() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.rules.cannonball_schedule = SpawnSchedule::Every { turns: 2, count: 1 };
+    assert_eq!(spawns_per_turn(state, 4), vec![0, 1, 0, 1]);
+}
+
+#[test]
+fn test_cannonballs_respawn_below_minimum() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.rules.cannonball_schedule = SpawnSchedule::BelowMinimum { min: 2, count: 2 };
+    let (next, events) = state.step(&[]);
+    assert_eq!(events.iter().filter(|e| matches!(e, GameEvent::CannonballSpawned { .. })).count(), 2);
+    assert!(next.cannonballs.len() >= 2);
+    // Respawned cannonballs never appear under a player.
+    assert!(!next.cannonballs.iter().any(|c| next.players.iter().any(|p| p.pos == c.pos)));
+}
+
+#[test]
+fn test_cannonball_schedule_can_be_set_per_mode() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.rules.cannonball_schedule = SpawnSchedule::Every { turns: 1, count: 1 };
+    state.rules.mode_cannonball_schedules.insert(GameMode::Classic, SpawnSchedule::Never);
+    assert_eq!(spawns_per_turn(state.clone(), 3), vec![0, 0, 0]);
+
+    state.mode = GameMode::Cracked;
+    assert_eq!(spawns_per_turn(state, 3), vec![1, 1, 1]);
+}
+
+#[test]
+fn test_blast_shapes_cover_expected_tiles() {
+    let target = Position { x: 2, y: 2 };
+    let origin = Position { x: 0, y: 2 };
+    assert_eq!(blast_tiles(BlastShape::Single, Topology::Square, target, origin, 5, 5), vec![target]);
+    assert_eq!(blast_tiles(BlastShape::Cross, Topology::Square, target, origin, 5, 5).len(), 5);
+    assert_eq!(blast_tiles(BlastShape::Square, Topology::Square, target, origin, 5, 5).len(), 9);
+    // A line goes away from the shooter and is clipped to the grid.
+    assert_eq!(
+        blast_tiles(BlastShape::Line { length: 4 }, Topology::Square, target, origin, 5, 5),
+        vec![Position { x: 2, y: 2 }, Position { x: 3, y: 2 }, Position { x: 4, y: 2 }],
+    );
+    // Blasts in a corner are clipped too.
+    assert_eq!(blast_tiles(BlastShape::Square, Topology::Square, Position { x: 0, y: 0 }, origin, 5, 5).len(), 4);
+}
+
+#[test]
+fn test_cross_blast_breaks_neighbours_and_eliminates() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.rules.blast_shape = BlastShape::Cross;
+    state.rules.tiles_broken_per_turn = 0;
+    state.players[0].cannonball_count = 1;
+    let (next, events) = state.step(&[(1, PlayerAction::Shoot { x: 4, y: 3 })]);
+
+    assert_eq!(events.iter().filter(|e| matches!(e, GameEvent::TileBroken { .. })).count(), 4);
+    assert!(events.contains(&GameEvent::PlayerEliminated { player_id: 2, pos: Position { x: 4, y: 4 } }));
+    assert!(!next.players[1].is_alive);
+}
+
+#[test]
+fn test_knockback_pushes_players_out_of_the_blast() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.rules.hit_effect = HitEffect::Knockback;
+    state.rules.tiles_broken_per_turn = 0;
+    state.players[0].cannonball_count = 1;
+    state.players[1].pos = Position { x: 2, y: 0 };
+    let (next, events) = state.step(&[(1, PlayerAction::Shoot { x: 2, y: 0 })]);
+
+    // Hit on the target tile: pushed along the shot, away from the shooter.
+    assert!(events.contains(&GameEvent::PlayerKnockedBack {
+        player_id: 2,
+        from: Some(Position { x: 2, y: 0 }),
+        to: Position { x: 3, y: 0 },
+    }));
+    assert!(next.players[1].is_alive);
+    assert_eq!(next.grid[0][2], Cell::Broken);
+}
+
+#[test]
+fn test_knockback_off_the_grid_is_lethal() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.rules.hit_effect = HitEffect::Knockback;
+    state.rules.tiles_broken_per_turn = 0;
+    state.players[0].cannonball_count = 1;
+    let (next, events) = state.step(&[(1, PlayerAction::Shoot { x: 4, y: 4 })]);
+
+    assert!(!events.iter().any(|e| matches!(e, GameEvent::PlayerKnockedBack { .. })));
+    assert!(!next.players[1].is_alive);
+}
+
+#[test]
+fn test_shot_range_limits_legal_targets() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.players[0].cannonball_count = 1;
+    state.players[0].pos = Position { x: 2, y: 2 };
+
+    state.rules.shot_range = Some(ShotRange { metric: DistanceMetric::Manhattan, max: 1 });
+    assert_eq!(legal_targets(&state, 0).len(), 5);
+    state.rules.shot_range = Some(ShotRange { metric: DistanceMetric::Chebyshev, max: 1 });
+    assert_eq!(legal_targets(&state, 0).len(), 9);
+    state.rules.shot_range = None;
+    assert_eq!(legal_targets(&state, 0).len(), 25);
+
+    // No ammunition, no target.
+    state.players[0].cannonball_count = 0;
+    assert!(state.shot_targets()[&1].is_empty());
+}
+
+#[test]
+fn test_out_of_range_shot_is_ignored() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.players[0].cannonball_count = 1;
+    state.rules.shot_range = Some(ShotRange { metric: DistanceMetric::Manhattan, max: 3 });
+    let (next, events) = state.step(&[(1, PlayerAction::Shoot { x: 4, y: 4 })]);
+
+    assert!(!events.iter().any(|e| matches!(e, GameEvent::ShotFired { .. })));
+    assert_eq!(next.players[0].cannonball_count, 1);
+    assert!(next.players[1].is_alive);
+}
+
+#[test]
+fn test_line_of_sight_is_blocked_by_players() {
+    let mut state = game(GameMode::Classic, player_infos(3), &[(0, 0), (2, 0), (4, 4)]);
+    state.players[0].cannonball_count = 1;
+    state.rules.shot_line_of_sight = true;
+
+    assert!(!is_legal_target(&state, 0, Position { x: 3, y: 0 }));
+    // The blocking player can still be targeted directly.
+    assert!(is_legal_target(&state, 0, Position { x: 2, y: 0 }));
+    assert!(is_legal_target(&state, 0, Position { x: 3, y: 1 }));
+
+    state.rules.shot_line_of_sight = false;
+    assert!(is_legal_target(&state, 0, Position { x: 3, y: 0 }));
+}
+
+#[test]
+fn test_validate_action_reports_specific_errors() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    let shoot = |x, y| PlayerAction::Shoot { x, y };
+
+    assert_eq!(state.validate_action(9, &PlayerAction::Move(Direction::Up)), Err(ActionError::UnknownPlayer));
+    assert_eq!(state.validate_action(1, &PlayerAction::Move(Direction::Up)), Err(ActionError::OutOfBounds));
+    assert_eq!(state.validate_action(1, &PlayerAction::Move(Direction::Stay)), Ok(()));
+    assert_eq!(state.validate_action(1, &PlayerAction::Move(Direction::Down)), Ok(()));
+    assert_eq!(state.validate_action(1, &shoot(1, 1)), Err(ActionError::NoAmmo));
+
+    state.players[0].cannonball_count = 1;
+    assert_eq!(state.validate_action(1, &shoot(1, 1)), Ok(()));
+    assert_eq!(state.validate_action(1, &shoot(5, 0)), Err(ActionError::OutOfBounds));
+    state.grid[1][1] = Cell::Broken;
+    assert_eq!(state.validate_action(1, &shoot(1, 1)), Err(ActionError::TargetBroken));
+    state.rules.shot_range = Some(ShotRange { metric: DistanceMetric::Chebyshev, max: 2 });
+    assert_eq!(state.validate_action(1, &shoot(3, 0)), Err(ActionError::OutOfRange));
+
+    state.players[1].is_alive = false;
+    assert_eq!(state.validate_action(2, &PlayerAction::Move(Direction::Stay)), Err(ActionError::PlayerEliminated));
+}
+
+#[test]
+fn test_shots_at_a_tile_another_player_chose_are_rejected() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.players[0].cannonball_count = 1;
+    state.players[1].cannonball_count = 1;
+    let shoot = PlayerAction::Shoot { x: 2, y: 2 };
+
+    assert_eq!(state.validate_turn_action(1, &shoot, [(2, &shoot)]), Err(ActionError::AlreadyTargeted));
+    assert_eq!(state.validate_turn_action(1, &shoot, [(1, &shoot)]), Ok(()));
+    assert_eq!(state.validate_turn_action(1, &PlayerAction::Shoot { x: 2, y: 3 }, [(2, &shoot)]), Ok(()));
+
+    // A frozen player's shot targets nothing, so it does not take the tile.
+    state.players[1].frozen = true;
+    assert_eq!(state.validate_turn_action(1, &shoot, [(2, &shoot)]), Ok(()));
+}
+
+#[test]
+fn test_out_of_bounds_shot_is_rejected_without_panicking() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.players[0].cannonball_count = 1;
+    let (next, events) = state.step(&[(1, PlayerAction::Shoot { x: 7, y: 9 })]);
+    assert!(!events.iter().any(|e| matches!(e, GameEvent::ShotFired { .. })));
+    assert_eq!(next.players[0].cannonball_count, 1);
+}
+
+#[test]
+fn test_walls_block_moves_and_shots() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.grid[0][1] = Cell::Wall;
+    state.players[0].cannonball_count = 1;
+
+    assert_eq!(state.validate_action(1, &PlayerAction::Move(Direction::Right)), Err(ActionError::Blocked));
+    assert_eq!(state.validate_action(1, &PlayerAction::Shoot { x: 1, y: 0 }), Err(ActionError::Blocked));
+    assert_eq!(state.validate_action(1, &PlayerAction::Shoot { x: 3, y: 0 }), Err(ActionError::Blocked));
+    assert_eq!(state.validate_action(1, &PlayerAction::Shoot { x: 0, y: 3 }), Ok(()));
+
+    let mut events = vec![];
+    resolve_moves(&mut state, &[(0, Direction::Right, 1)], &mut events);
+    assert_eq!(state.players[0].pos, Position { x: 0, y: 0 });
+}
+
+#[test]
+fn test_ice_slides_until_non_ice_tile() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.grid[0][1] = Cell::Ice;
+    state.grid[0][2] = Cell::Ice;
+    let (next, events) = state.step(&[(1, PlayerAction::Move(Direction::Right))]);
+    assert_eq!(next.players[0].pos, Position { x: 3, y: 0 });
+    assert!(events.contains(&GameEvent::PlayerMoved {
+        player_id: 1,
+        from: Some(Position { x: 0, y: 0 }),
+        to: Position { x: 3, y: 0 },
+    }));
+
+    // A wall stops the slide on the last ice tile.
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.grid[0][1] = Cell::Ice;
+    state.grid[0][2] = Cell::Wall;
+    let (next, _) = state.step(&[(1, PlayerAction::Move(Direction::Right))]);
+    assert_eq!(next.players[0].pos, Position { x: 1, y: 0 });
+}
+
+#[test]
+fn test_teleporters_send_players_to_their_pair() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.grid[0][1] = Cell::Teleporter { channel: 1 };
+    state.grid[3][3] = Cell::Teleporter { channel: 1 };
+    state.grid[2][2] = Cell::Teleporter { channel: 2 };
+    let (next, events) = state.step(&[(1, PlayerAction::Move(Direction::Right))]);
+
+    assert_eq!(next.players[0].pos, Position { x: 3, y: 3 });
+    assert!(events.contains(&GameEvent::PlayerTeleported {
+        player_id: 1,
+        from: Some(Position { x: 1, y: 0 }),
+        to: Position { x: 3, y: 3 },
+    }));
+}
+
+#[test]
+fn test_walls_and_spawn_pads_never_break() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.rules.blast_shape = BlastShape::Square;
+    state.rules.tiles_broken_per_turn = 25;
+    state.grid[2][2] = Cell::Wall;
+    state.grid[2][3] = Cell::SpawnPad;
+    state.players[0].cannonball_count = 1;
+    let (next, _) = state.step(&[(1, PlayerAction::Shoot { x: 3, y: 3 })]);
+
+    assert_eq!(next.grid[2][2], Cell::Wall);
+    assert_eq!(next.grid[2][3], Cell::SpawnPad);
+    assert_eq!(next.grid.iter().flatten().filter(|&&c| c == Cell::Broken).count(), 23);
+}
+
+#[test]
+fn test_players_spawn_on_spawn_pads_first() {
+    let mut grid = generate_grid(5, 5);
+    grid[1][1] = Cell::SpawnPad;
+    grid[3][3] = Cell::SpawnPad;
+    let mut rng = GameRng::new(3);
+    let mut players = vec![];
+    for id in 1..=3 {
+        players.push(spawn_random_player(&grid, &players, id, format!("player{}", id), &mut rng).unwrap());
+    }
+    let on_pads = players.iter().filter(|p| grid[p.pos.y][p.pos.x] == Cell::SpawnPad).count();
+    assert_eq!(on_pads, 2);
+}
+
+#[test]
+fn test_items_are_picked_up_into_inventory() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.items = vec![Item { pos: Position { x: 1, y: 0 }, kind: ItemKind::Dash }];
+    let (next, events) = state.step(&[(1, PlayerAction::Move(Direction::Right))]);
+    assert_eq!(next.players[0].inventory, vec![ItemKind::Dash]);
+    assert!(next.items.is_empty());
+    assert!(events.contains(&GameEvent::ItemPickedUp { player_id: 1, pos: Position { x: 1, y: 0 }, item: ItemKind::Dash }));
+}
+
+#[test]
+fn test_items_spawn_from_the_mode_pool_only() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.rules.item_schedule = SpawnSchedule::Every { turns: 1, count: 1 };
+    let (next, _) = state.step(&[]);
+    assert!(next.items.is_empty());
+
+    state.rules.mode_item_pools.insert(GameMode::Classic, vec![ItemKind::Freeze]);
+    let (next, events) = state.step(&[]);
+    assert_eq!(next.items.len(), 1);
+    assert_eq!(next.items[0].kind, ItemKind::Freeze);
+    assert!(events.iter().any(|e| matches!(e, GameEvent::ItemSpawned { item: ItemKind::Freeze, .. })));
+}
+
+#[test]
+fn test_king_of_the_hill_spawns_items_next_to_players() {
+    let mut state = game(GameMode::KingOfTheHill, player_infos(2), &[(2, 1), (4, 4)]);
+    assert_eq!(state.rules.item_pool_for(GameMode::KingOfTheHill).len(), 4);
+    state.rules.item_schedule = SpawnSchedule::Every { turns: 1, count: 1 };
+    // The only free tile touches player 1, closer than cannonballs may spawn.
+    for row in state.grid.iter_mut() {
+        row.fill(Cell::Wall);
+    }
+    state.grid[1][2] = Cell::Solid;
+    state.grid[4][4] = Cell::Solid;
+    state.grid[1][3] = Cell::Hill;
+
+    let (next, events) = state.step(&[]);
+    assert_eq!(next.items.len(), 1);
+    assert_eq!(next.items[0].pos, Position { x: 3, y: 1 });
+    assert!(events.iter().any(|e| matches!(e, GameEvent::ItemSpawned { .. })));
+}
+
+#[test]
+fn test_using_an_item_requires_holding_it() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    let dash = PlayerAction::UseItem(ItemUse::Dash(Direction::Right));
+    assert_eq!(state.validate_action(1, &dash), Err(ActionError::NoItem));
+    state.players[0].inventory.push(ItemKind::Dash);
+    assert_eq!(state.validate_action(1, &dash), Ok(()));
+    assert_eq!(state.validate_action(1, &PlayerAction::UseItem(ItemUse::Dash(Direction::Up))), Err(ActionError::OutOfBounds));
+}
+
+#[test]
+fn test_dash_moves_two_tiles_and_consumes_the_item() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.players[0].inventory.push(ItemKind::Dash);
+    let (next, events) = state.step(&[(1, PlayerAction::UseItem(ItemUse::Dash(Direction::Down)))]);
+    assert_eq!(next.players[0].pos, Position { x: 0, y: 2 });
+    assert!(next.players[0].inventory.is_empty());
+    assert!(events.contains(&GameEvent::ItemUsed { player_id: 1, item: ItemKind::Dash }));
+}
+
+#[test]
+fn test_shield_absorbs_one_fall() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.rules.tiles_broken_per_turn = 0;
+    state.players[0].inventory.push(ItemKind::Shield);
+    let (mut next, _) = state.step(&[(1, PlayerAction::UseItem(ItemUse::Shield))]);
+    assert!(next.players[0].shielded);
+
+    next.grid[0][1] = Cell::Broken;
+    let (next, events) = next.step(&[(1, PlayerAction::Move(Direction::Right))]);
+    assert!(next.players[0].is_alive);
+    assert!(!next.players[0].shielded);
+    assert_eq!(next.grid[0][1], Cell::Solid);
+    assert!(events.contains(&GameEvent::ShieldAbsorbed { player_id: 1, pos: Position { x: 1, y: 0 } }));
+}
+
+#[test]
+fn test_repair_kit_restores_an_adjacent_broken_tile() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.rules.tiles_broken_per_turn = 0;
+    state.players[0].inventory.push(ItemKind::RepairKit);
+    state.grid[0][1] = Cell::Broken;
+    state.grid[0][2] = Cell::Broken;
+
+    let far = PlayerAction::UseItem(ItemUse::RepairKit { x: 2, y: 0 });
+    assert_eq!(state.validate_action(1, &far), Err(ActionError::OutOfRange));
+    let solid = PlayerAction::UseItem(ItemUse::RepairKit { x: 0, y: 1 });
+    assert_eq!(state.validate_action(1, &solid), Err(ActionError::TargetNotBroken));
+
+    let (next, events) = state.step(&[(1, PlayerAction::UseItem(ItemUse::RepairKit { x: 1, y: 0 }))]);
+    assert_eq!(next.grid[0][1], Cell::Solid);
+    assert!(events.contains(&GameEvent::TileRepaired { pos: Position { x: 1, y: 0 } }));
+}
+
+#[test]
+fn test_freeze_skips_the_opponents_next_action() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.players[0].inventory.push(ItemKind::Freeze);
+    assert_eq!(
+        state.validate_action(1, &PlayerAction::UseItem(ItemUse::Freeze { player_id: 1 })),
+        Err(ActionError::InvalidTarget),
+    );
+
+    let (next, _) = state.step(&[(1, PlayerAction::UseItem(ItemUse::Freeze { player_id: 2 }))]);
+    assert!(next.players[1].frozen);
+
+    let (next, events) = next.step(&[(2, PlayerAction::Move(Direction::Up))]);
+    assert_eq!(next.players[1].pos, Position { x: 4, y: 4 });
+    assert!(events.contains(&GameEvent::ActionSkipped { player_id: 2 }));
+    assert!(!next.players[1].frozen);
+}
+
+#[test]
+fn test_every_registered_mode_is_offered_and_found() {
+    assert_eq!(available_modes(), vec![GameMode::Classic, GameMode::Cracked, GameMode::ShrinkingRing, GameMode::SpreadingLava, GameMode::Teams, GameMode::KingOfTheHill, GameMode::FogOfWar]);
+    for rules in MODES {
+        assert_eq!(mode_rules(rules.mode()).mode(), rules.mode());
+    }
+}
+
+#[test]
+fn test_mode_hooks_default_to_standard_rules() {
+    /// A mode that breaks no tile and forbids shooting.
+    struct Pacifist;
+    impl ModeRules for Pacifist {
+        fn mode(&self) -> GameMode {
+            GameMode::Classic
+        }
+        fn end_of_turn_hazard(&self, _: &mut GameState, _: &mut Vec<GameEvent>) {}
+        fn allows_action(&self, action: &PlayerAction) -> bool {
+            !matches!(action, PlayerAction::Shoot { .. })
+        }
+    }
+
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    assert!(Pacifist.allows_action(&PlayerAction::Move(Direction::Up)));
+    assert!(!Pacifist.allows_action(&PlayerAction::Shoot { x: 0, y: 0 }));
+    assert_eq!(Pacifist.cannonball_schedule(&state.rules), state.rules.cannonball_schedule);
+    assert!(Pacifist.outcome(&state).is_none());
+    state.players[1].is_alive = false;
+    assert_eq!(Pacifist.outcome(&state), state.outcome());
+}
+
+#[test]
+fn test_shrinking_ring_cracks_then_breaks_the_outer_ring() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.mode = GameMode::ShrinkingRing;
+    state.rules.ring_shrink_interval = 2;
+    state.players[0].pos = Position { x: 1, y: 1 };
+    state.players[1].pos = Position { x: 2, y: 2 };
+
+    // Nothing happens before the first shrink.
+    let (state, events) = state.step(&[]);
+    assert!(!events.iter().any(|e| matches!(e, GameEvent::TileCracked { .. } | GameEvent::TileBroken { .. })));
+
+    // Turn 2: the 16 border tiles crack.
+    let (state, events) = state.step(&[]);
+    assert_eq!(events.iter().filter(|e| matches!(e, GameEvent::TileCracked { .. })).count(), 16);
+    assert_eq!(state.grid[0][0], Cell::Cracked);
+    assert_eq!(state.grid[1][1], Cell::Solid);
+
+    // Turn 3: they break, the inner tiles are untouched.
+    let (state, events) = state.step(&[]);
+    assert_eq!(events.iter().filter(|e| matches!(e, GameEvent::TileBroken { .. })).count(), 16);
+    assert_eq!(state.grid[4][4], Cell::Broken);
+    assert!(state.players.iter().all(|p| p.is_alive));
+
+    // Turn 4: the next ring cracks, under the first player.
+    let (state, _) = state.step(&[]);
+    assert_eq!(state.grid[1][1], Cell::Cracked);
+    assert_eq!(state.grid[2][2], Cell::Solid);
+}
+
+#[test]
+fn test_shrinking_ring_skips_rings_already_gone() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(1, 1), (2, 2)]);
+    state.mode = GameMode::ShrinkingRing;
+    state.rules.ring_shrink_interval = 2;
+    // The border is all walls and holes.
+    for (y, row) in state.grid.iter_mut().enumerate() {
+        for (x, cell) in row.iter_mut().enumerate() {
+            if ring_index(Position { x, y }, 5, 5) == 0 {
+                *cell = if x == 0 { Cell::Wall } else { Cell::Broken };
+            }
         }
     }
 
-    #[test]
-    fn test_spawn_player_no_space() {
-        let mut grid = vec![vec![Cell::Lava; 5]; 5]; // No valid tiles.
-        let players = vec![];
-        let player = spawn_random_player(&grid, &players, 0);
-        assert!(player.is_none());
+    // Turn 2: the first shrink goes straight to the ring inside the border.
+    let (state, _) = state.step(&[]);
+    let (state, events) = state.step(&[]);
+    assert_eq!(events.iter().filter(|e| matches!(e, GameEvent::TileCracked { .. })).count(), 8);
+    assert_eq!(state.grid[1][1], Cell::Cracked);
+    assert_eq!(state.grid[2][2], Cell::Solid);
+
+    // Turn 4: then the center.
+    let (state, _) = state.step(&[]);
+    let (state, _) = state.step(&[]);
+    assert_eq!(state.grid[2][2], Cell::Cracked);
+}
+
+#[test]
+fn test_lava_spread_is_orthogonal_and_one_tile_per_step() {
+    let mut grid = generate_grid(5, 5);
+    grid[2][2] = Cell::Broken;
+    grid[2][3] = Cell::Wall;
+    let mut rng = GameRng::new(1);
+
+    let spread = lava_spread(&grid, Topology::Square, 100, &mut rng);
+    assert_eq!(spread, vec![
+        Position { x: 2, y: 1 },
+        Position { x: 1, y: 2 },
+        Position { x: 2, y: 3 },
+    ]);
+    assert!(lava_spread(&grid, Topology::Square, 0, &mut rng).is_empty());
+}
+
+#[test]
+fn test_lava_spread_rolls_once_per_broken_neighbour() {
+    let mut grid = generate_grid(1, 3);
+    grid[0][0] = Cell::Broken;
+    grid[0][2] = Cell::Broken;
+    let mut rng = GameRng::new(1);
+
+    // Two lava neighbours at 50% each set the middle tile on fire 75% of the time.
+    let fires = (0..2000).filter(|_| !lava_spread(&grid, Topology::Square, 50, &mut rng).is_empty()).count();
+    assert!((1400..1600).contains(&fires), "{} fires", fires);
+}
+
+#[test]
+fn test_spreading_lava_telegraphs_before_breaking() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.mode = GameMode::SpreadingLava;
+    state.rules.lava_spread_chance = 100;
+    state.grid[2][2] = Cell::Broken;
+
+    let (state, events) = state.step(&[]);
+    assert!(!events.iter().any(|e| matches!(e, GameEvent::TileBroken { .. })));
+    assert_eq!(events.iter().filter(|e| matches!(e, GameEvent::TileCracked { .. })).count(), 4);
+    assert_eq!(state.grid[1][2], Cell::Cracked);
+
+    let (state, _) = state.step(&[]);
+    assert_eq!(state.grid[1][2], Cell::Broken);
+    assert_eq!(state.grid[0][2], Cell::Cracked);
+    assert_eq!(state.grid[1][1], Cell::Cracked);
+}
+
+#[test]
+fn test_spreading_lava_starts_a_pool_on_an_intact_grid() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.mode = GameMode::SpreadingLava;
+
+    let (_, events) = state.step(&[]);
+    assert_eq!(
+        events.iter().filter(|e| matches!(e, GameEvent::TileCracked { .. })).count(),
+        state.rules.tiles_broken_per_turn,
+    );
+}
+
+#[test]
+fn test_assign_teams_is_balanced() {
+    let mut rng = GameRng::new(3);
+    for (count, teams) in [(3, 2), (4, 2), (5, 3)] {
+        let mut infos = player_infos(count);
+        assign_teams(&mut infos, teams, &mut rng);
+        let sizes: Vec<usize> = (1..=teams)
+            .map(|t| infos.iter().filter(|p| p.team_id == Some(t)).count())
+            .collect();
+        assert_eq!(sizes.iter().sum::<usize>(), count);
+        assert!(sizes.iter().max().unwrap() - sizes.iter().min().unwrap() <= 1);
     }
 
-    #[test]
-    fn test_cannonball_spawn_limit() {
-        let grid = create_grid(5, 5);
-        let players = vec![];
-        let cannonballs = spawn_random_cannonballs(&grid, &players, 0, 100);
-        // There should never be more cannonballs than solid tiles.
-        assert!(cannonballs.len() <= 25);
+    let mut infos = player_infos(3);
+    assign_teams(&mut infos, 1, &mut rng);
+    assert!(infos.iter().all(|p| p.team_id.is_none()));
+}
+
+#[test]
+fn test_teams_are_ignored_outside_team_modes() {
+    let mut infos = player_infos(2);
+    infos[0].team_id = Some(1);
+    infos[1].team_id = Some(1);
+    let state = GameState::new(GameRules::default(), infos.clone(), GameMode::Classic, 7);
+    assert!(state.players.iter().all(|p| p.team_id.is_none()));
+
+    let state = GameState::new(GameRules::default(), infos, GameMode::Teams, 7);
+    assert!(state.players.iter().all(|p| p.team_id == Some(1)));
+}
+
+#[test]
+fn test_last_team_standing_wins_together() {
+    let mut state = game(GameMode::Teams, team_infos(&[1, 2, 1, 2]), &[(0, 0), (4, 4), (0, 4), (4, 0)]);
+    state.players[1].is_alive = false;
+    state.players[1].eliminated_turn = Some(2);
+    assert!(state.outcome().is_none());
+
+    state.players[3].is_alive = false;
+    state.players[3].eliminated_turn = Some(4);
+    let outcome = state.outcome().unwrap();
+    assert_eq!(outcome.result, GameResult::TeamWinner { team_id: 1, player_ids: vec![1, 3] });
+    let ranks: Vec<(u8, u8)> = outcome.placements.iter().map(|p| (p.player_id, p.rank)).collect();
+    assert_eq!(ranks, vec![(1, 1), (3, 1), (2, 3), (4, 3)]);
+}
+
+#[test]
+fn test_blasts_spare_teammates_without_friendly_fire() {
+    let mut state = game(GameMode::Teams, team_infos(&[1, 2, 1, 2]), &[(0, 0), (4, 4), (0, 4), (4, 0)]);
+    state.players[0].cannonball_count = 2;
+    state.players[2].pos = Position { x: 0, y: 2 };
+
+    // Player 1 fires on their teammate (player 3): the tile holds.
+    let (next, _) = state.step(&[(1, PlayerAction::Shoot { x: 0, y: 2 })]);
+    assert_eq!(next.grid[2][0], Cell::Solid);
+    assert!(next.players[2].is_alive);
+
+    // With friendly fire, the same shot breaks the tile and player 3 falls next turn.
+    state.rules.friendly_fire = true;
+    let (next, _) = state.step(&[(1, PlayerAction::Shoot { x: 0, y: 2 })]);
+    assert_eq!(next.grid[2][0], Cell::Broken);
+    let (next, _) = next.step(&[]);
+    assert!(!next.players[2].is_alive);
+}
+
+#[test]
+fn test_hill_covers_the_center_of_the_grid() {
+    let state = game(GameMode::KingOfTheHill, player_infos(2), &[(2, 1), (4, 4)]);
+    let hills = state.grid.iter().flatten().filter(|c| **c == Cell::Hill).count();
+    assert_eq!(hills, 1);
+    assert_eq!(state.grid[2][2], Cell::Hill);
+
+    let rules = GameRules { grid_cols: 6, ..GameRules::default() };
+    let state = GameState::new(rules, player_infos(2), GameMode::KingOfTheHill, 7);
+    assert_eq!(state.grid[2][2], Cell::Hill);
+    assert_eq!(state.grid[2][3], Cell::Hill);
+    assert!(state.players.iter().all(|p| state.grid[p.pos.y][p.pos.x] != Cell::Hill));
+}
+
+#[test]
+fn test_hill_stays_off_holes_and_walls_of_bundled_maps() {
+    for map in bundled_maps() {
+        let state = GameState::new_on_map(GameRules::default(), player_infos(2), GameMode::KingOfTheHill, &map, 3);
+        let hills: Vec<Position> = state.grid.iter().enumerate()
+            .flat_map(|(y, row)| row.iter().enumerate().filter(|(_, c)| **c == Cell::Hill).map(move |(x, _)| Position { x, y }))
+            .collect();
+        assert_eq!(hills.len(), 1, "{}", map.name);
+        let pos = hills[0];
+        assert!(matches!(map.grid[pos.y][pos.x], Cell::Solid | Cell::Ice), "{}", map.name);
+        // No hole or wall was replaced.
+        for (y, row) in map.grid.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if matches!(cell, Cell::Broken | Cell::Wall) {
+                    assert_eq!(state.grid[y][x], *cell, "{}", map.name);
+                }
+            }
+        }
     }
+}
+
+#[test]
+fn test_lone_player_on_the_hill_scores() {
+    let state = game(GameMode::KingOfTheHill, player_infos(2), &[(2, 1), (4, 4)]);
+    let (state, events) = state.step(&[(1, PlayerAction::Move(Direction::Down))]);
+    assert!(events.contains(&GameEvent::PointScored { player_id: 1, pos: Position { x: 2, y: 2 }, score: 1 }));
+    assert_eq!(state.players[0].score, 1);
+
+    // Standing still on the hill keeps scoring.
+    let (state, _) = state.step(&[]);
+    assert_eq!(state.players[0].score, 2);
+    assert_eq!(state.players[1].score, 0);
+}
+
+#[test]
+fn test_hill_game_ends_at_target_score_or_turn_limit() {
+    let mut state = game(GameMode::KingOfTheHill, player_infos(2), &[(2, 1), (4, 4)]);
+    state.rules.target_score = 2;
+    state.players[0].score = 1;
+    assert!(state.outcome().is_none());
+
+    state.players[0].score = 2;
+    let outcome = state.outcome().unwrap();
+    assert_eq!(outcome.result, GameResult::Winner { player_id: 1 });
 
-    #[test]
-    fn test_move_player_into_lava() {
-        let mut grid = create_grid(5, 5);
-        let mut player = Player::new(1, Position { x: 2, y: 2 });
+    // Time up with equal scores: draw.
+    state.players[1].score = 2;
+    state.rules.target_score = 5;
+    state.turn = state.rules.turn_limit + 1;
+    let outcome = state.outcome().unwrap();
+    assert_eq!(outcome.result, GameResult::Draw { player_ids: vec![1, 2] });
+}
 
-        grid[2][3] = Cell::Lava;
-        move_player(&mut player, Direction::Right, &grid);
+#[test]
+fn test_fog_hides_distant_tiles_and_players() {
+    let mut state = game(GameMode::FogOfWar, player_infos(2), &[(0, 0), (4, 4)]);
+    state.rules.vision_radius = 1;
+    state.cannonballs.push(Cannonball { pos: Position { x: 1, y: 0 } });
+    state.cannonballs.push(Cannonball { pos: Position { x: 3, y: 3 } });
 
-        // Player should die if moving into lava.
-        assert!(!player.is_alive);
+    let view = Vision::of(&state, Some(1)).view(&state);
+    assert_eq!(view.grid[0][1], Some(Cell::Solid));
+    assert_eq!(view.grid[1][1], None);
+    assert_eq!(view.players.iter().map(|p| p.id).collect::<Vec<_>>(), vec![1]);
+    assert_eq!(view.cannonballs.len(), 1);
+
+    // Spectators see everything.
+    let view = Vision::of(&state, None).view(&state);
+    assert!(view.grid.iter().flatten().all(|c| c.is_some()));
+    assert_eq!(view.players.len(), 2);
+    assert_eq!(view.cannonballs.len(), 2);
+}
+
+#[test]
+fn test_fog_hides_the_shooter_of_a_targeted_tile() {
+    let mut state = game(GameMode::FogOfWar, player_infos(2), &[(0, 0), (4, 4)]);
+    state.rules.vision_radius = 1;
+    let shot = |x, y, origin: (usize, usize), team_id| TargetedTile {
+        x,
+        y,
+        origin: Position { x: origin.0, y: origin.1 },
+        team_id,
+    };
+    state.targeted_tiles = vec![shot(1, 0, (4, 4), Some(2)), shot(0, 1, (0, 0), Some(1)), shot(3, 3, (4, 4), Some(2))];
+
+    // Player 1 sees both tiles next to them, but not who fired from the far corner.
+    let view = Vision::of(&state, Some(1)).view(&state);
+    let seen: Vec<_> = view.targeted_tiles.iter().map(|t| (t.x, t.y, t.origin, t.team_id)).collect();
+    assert_eq!(seen, vec![(1, 0, None, None), (0, 1, Some(Position { x: 0, y: 0 }), Some(1))]);
+
+    // Spectators see every shooter.
+    let view = Vision::of(&state, None).view(&state);
+    assert!(view.targeted_tiles.iter().all(|t| t.origin.is_some() && t.team_id.is_some()));
+    assert_eq!(view.targeted_tiles.len(), 3);
+}
+
+#[test]
+fn test_no_fog_outside_fog_of_war() {
+    let state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    let view = Vision::of(&state, Some(1)).view(&state);
+    assert!(view.grid.iter().flatten().all(|c| c.is_some()));
+    assert_eq!(view.players.len(), 2);
+}
+
+#[test]
+fn test_fog_filters_events_and_shot_targets() {
+    let mut state = game(GameMode::FogOfWar, player_infos(2), &[(0, 0), (4, 4)]);
+    state.rules.vision_radius = 1;
+    state.players[0].cannonball_count = 1;
+    state.players[1].cannonball_count = 1;
+    let (state, events) = state.step(&[
+        (1, PlayerAction::Move(Direction::Right)),
+        (2, PlayerAction::Move(Direction::Left)),
+    ]);
+    let vision = Vision::of(&state, Some(1));
+
+    let seen = vision.events(&state, &events);
+    assert!(seen.contains(&GameEvent::PlayerMoved { player_id: 1, from: Some(Position { x: 0, y: 0 }), to: Position { x: 1, y: 0 } }));
+    assert!(!seen.iter().any(|e| e.players().contains(&2)));
+
+    let targets = vision.shot_targets(&state.shot_targets());
+    assert_eq!(targets.keys().copied().collect::<Vec<_>>(), vec![1]);
+    assert_eq!(Vision::of(&state, None).shot_targets(&state.shot_targets()).len(), 2);
+}
+
+#[test]
+fn test_fog_hides_where_a_player_came_from() {
+    let mut state = game(GameMode::FogOfWar, player_infos(2), &[(0, 0), (4, 4)]);
+    state.rules.vision_radius = 2;
+    state.players[1].pos = Position { x: 2, y: 1 };
+    let (state, events) = state.step(&[
+        (1, PlayerAction::Move(Direction::Stay)),
+        (2, PlayerAction::Move(Direction::Left)),
+    ]);
+    let seen = Vision::of(&state, Some(1)).events(&state, &events);
+    assert!(seen.contains(&GameEvent::PlayerMoved { player_id: 2, from: None, to: Position { x: 1, y: 1 } }));
+    let own = Vision::of(&state, Some(2)).events(&state, &events);
+    assert!(own.contains(&GameEvent::PlayerMoved { player_id: 2, from: Some(Position { x: 2, y: 1 }), to: Position { x: 1, y: 1 } }));
+}
+
+#[test]
+fn test_fog_radius_follows_topology() {
+    let mut state = game(GameMode::FogOfWar, player_infos(2), &[(0, 0), (4, 4)]);
+    state.rules.vision_radius = 1;
+    assert!(!Vision::of(&state, Some(1)).sees(Position { x: 1, y: 1 }));
+    state.rules.topology = Topology::EightWay;
+    assert!(Vision::of(&state, Some(1)).sees(Position { x: 1, y: 1 }));
+    state.rules.topology = Topology::Hex;
+    // Two moves away on hexagons: (0, 0) and (1, 1) do not touch in odd-r offset.
+    assert!(!Vision::of(&state, Some(1)).sees(Position { x: 1, y: 1 }));
+    assert!(Vision::of(&state, Some(1)).sees(Position { x: 0, y: 1 }));
+}
+
+#[test]
+fn test_bots_only_see_their_seat_view() {
+    let mut state = game(GameMode::FogOfWar, player_infos(2), &[(0, 0), (4, 4)]);
+    state.rules.vision_radius = 1;
+    state.grid[4][4] = Cell::Broken;
+    state.cannonballs.push(Cannonball { pos: Position { x: 3, y: 4 } });
+    let view = Vision::of(&state, Some(1)).redact(&state);
+    assert_eq!(view.players.iter().map(|p| p.id).collect::<Vec<_>>(), vec![1]);
+    assert!(view.cannonballs.is_empty());
+    assert_eq!(view.grid[4][4], Cell::Solid);
+
+    // Bots are looked up by player ID, whatever their index in the state.
+    state.players.remove(0);
+    assert!(matches!(bot_action(AgentKind::Greedy, &state, 1), PlayerAction::Move(Direction::Stay)));
+    let action = bot_action(AgentKind::Greedy, &state, 2);
+    assert!(state.validate_action(2, &action).is_ok());
+}
+
+#[test]
+fn test_eliminated_players_see_everything() {
+    let mut state = game(GameMode::FogOfWar, player_infos(2), &[(0, 0), (4, 4)]);
+    state.rules.vision_radius = 1;
+    state.players[0].is_alive = false;
+    let view = Vision::of(&state, Some(1)).view(&state);
+    assert!(view.grid.iter().flatten().all(|c| c.is_some()));
+}
+
+#[test]
+fn test_parse_map() {
+    let map = GameMap::parse("name: Test\nS.o\n#1x\n1\n").unwrap();
+    assert_eq!(map.name, "Test");
+    assert_eq!(map.grid, vec![
+        vec![Cell::SpawnPad, Cell::Solid, Cell::Solid],
+        vec![Cell::Wall, Cell::Teleporter { channel: 1 }, Cell::Broken],
+        // Short rows are padded with broken tiles.
+        vec![Cell::Teleporter { channel: 1 }, Cell::Broken, Cell::Broken],
+    ]);
+    assert_eq!(map.cannonballs, vec![Position { x: 2, y: 0 }]);
+}
+
+#[test]
+fn test_parse_map_errors() {
+    assert_eq!(GameMap::parse("S..\n"), Err(MapError::MissingName));
+    assert_eq!(GameMap::parse("name: Empty\n"), Err(MapError::Empty));
+    assert_eq!(GameMap::parse("name: Bad\n..\n.?\n"), Err(MapError::UnknownTile { row: 1, col: 1, tile: '?' }));
+    assert_eq!(GameMap::parse("name: Lonely\n.2.\n"), Err(MapError::UnpairedTeleporter { channel: 2 }));
+}
+
+#[test]
+fn test_bundled_maps_are_playable() {
+    let maps = bundled_maps();
+    assert!(!maps.is_empty());
+    for map in &maps {
+        let spawns = map.grid.iter().flatten().filter(|c| **c == Cell::SpawnPad).count();
+        assert!(spawns >= crate::config::matchmaking::MAX_PLAYERS, "{} lacks spawn pads", map.name);
+        assert_eq!(bundled_map(&map.name).as_ref(), Some(map));
     }
+    // Bundled maps, then the generated one.
+    assert_eq!(map_names().len(), maps.len() + 1);
+}
+
+#[test]
+fn test_game_on_a_map_uses_its_spawns_and_cannonballs() {
+    let map = bundled_map("Donut").unwrap();
+    let state = GameState::new_on_map(GameRules::default(), player_infos(3), GameMode::Classic, &map, 7);
+    assert_eq!(state.grid.len(), 7);
+    assert!(state.players.iter().all(|p| state.grid[p.pos.y][p.pos.x] == Cell::SpawnPad));
+    let cannonballs: Vec<Position> = state.cannonballs.iter().map(|c| c.pos).collect();
+    assert_eq!(cannonballs, map.cannonballs);
+}
 
-    #[test]
-    fn test_pickup_cannonball() {
-        let grid = create_grid(5, 5);
-        let mut player = Player::new(1, Position { x: 2, y: 2 });
-        let cannonball_pos = Position { x: 2, y: 3 };
-        let mut cannonballs = vec![Cannonball { pos: cannonball_pos }];
+/// Nearest cannonball walking distance from each spawn pad of a map.
+fn spawn_distances_to_cannonballs(map: &GameMap, topology: Topology) -> Vec<Option<usize>> {
+    let spawns = map.grid.iter().enumerate().flat_map(|(y, row)| {
+        row.iter().enumerate().filter(|(_, c)| **c == Cell::SpawnPad).map(move |(x, _)| Position { x, y })
+    });
+    spawns
+        .map(|spawn| {
+            let distances = walk_distances(&map.grid, topology, spawn);
+            map.cannonballs.iter().filter_map(|c| distances[c.y][c.x]).min()
+        })
+        .collect()
+}
 
-        move_player(&mut player, Direction::Right, &grid);
-        try_pickup_cannonball(&mut player, &mut cannonballs);
+#[test]
+fn test_generated_maps_are_symmetric_and_connected() {
+    for (rows, cols) in [(5, 5), (7, 7), (6, 8)] {
+        for seed in 0..50 {
+            let map = generate_map(rows, cols, Topology::Square, seed);
+            assert_eq!(map, generate_map(rows, cols, Topology::Square, seed));
+            for y in 0..rows {
+                for x in 0..cols {
+                    let cell = map.grid[y][x];
+                    assert_eq!(cell, map.grid[y][cols - 1 - x], "seed {} not mirrored", seed);
+                    assert_eq!(cell, map.grid[rows - 1 - y][x], "seed {} not mirrored", seed);
+                }
+            }
 
-        // Player should pick up the cannonball.
-        assert_eq!(player.cannonball_count, 1);
-        assert!(cannonballs.is_empty());
+            let open = |c: &Cell| c.is_walkable() && *c != Cell::Broken;
+            let distances = walk_distances(&map.grid, Topology::Square, Position { x: 0, y: 0 });
+            for (y, row) in map.grid.iter().enumerate() {
+                for (x, cell) in row.iter().enumerate() {
+                    assert!(!open(cell) || distances[y][x].is_some(), "seed {}: ({}, {}) unreachable", seed, x, y);
+                }
+            }
+        }
     }
+}
 
-    #[test]
-    fn test_break_tile_replaces_with_lava() {
-        let mut grid = create_grid(5, 5);
-        let mut rng = rand::rng();
+#[test]
+fn test_generated_maps_are_fair_to_every_spawn() {
+    for seed in 0..50 {
+        let map = generate_map(7, 7, Topology::Square, seed);
+        assert_eq!(map.grid.iter().flatten().filter(|c| **c == Cell::SpawnPad).count(), 4);
+        assert!(!map.cannonballs.is_empty());
+        let distances = spawn_distances_to_cannonballs(&map, Topology::Square);
+        assert!(distances[0].is_some(), "seed {}: cannonballs unreachable", seed);
+        assert!(distances.iter().all(|d| *d == distances[0]), "seed {}: unfair distances {:?}", seed, distances);
+    }
+}
 
-        break_tile(&mut grid, &mut rng);
-        let lava_count = grid.iter().flatten().filter(|&&c| c == Cell::Lava).count();
-        assert_eq!(lava_count, 1);
+#[test]
+fn test_small_generated_maps_always_get_cannonballs() {
+    // A generated layout always comes with a group of cannonballs: only the open fallback,
+    // which gets random ones in game, has none.
+    for (rows, cols, topology) in [(3, 3, Topology::Square), (3, 4, Topology::EightWay), (3, 3, Topology::Hex), (4, 3, Topology::Hex)] {
+        for seed in 0..50 {
+            let map = generate_map(rows, cols, topology, seed);
+            let open = map.grid.iter().flatten().all(|c| matches!(c, Cell::Solid | Cell::SpawnPad));
+            assert!(!map.cannonballs.is_empty() || open, "{:?} seed {}: no cannonball", topology, seed);
+        }
     }
+}
+
+#[test]
+fn test_generated_hex_maps_keep_hex_symmetry_and_fairness() {
+    for (rows, cols) in [(5, 5), (7, 7), (6, 8)] {
+        for seed in 0..50 {
+            let map = generate_map(rows, cols, Topology::Hex, seed);
+            let topology = Topology::Hex;
+            // The symmetry is a hexagonal automorphism: neighbours stay neighbours.
+            let image_of = |p: Position| match rows % 2 {
+                1 => Position { x: p.x, y: rows - 1 - p.y },
+                _ => Position { x: cols - 1 - p.x, y: rows - 1 - p.y },
+            };
+            for y in 0..rows {
+                for x in 0..cols {
+                    let pos = Position { x, y };
+                    let image = image_of(pos);
+                    assert!(mirrored(pos, rows, cols, topology).contains(&image));
+                    assert_eq!(map.grid[y][x], map.grid[image.y][image.x], "seed {} not mirrored", seed);
+                    for next in topology.neighbours(pos, rows, cols) {
+                        assert_eq!(topology.distance(image, image_of(next)), 1, "{}x{}: broken symmetry", rows, cols);
+                    }
+                }
+            }
+
+            let open = |c: &Cell| c.is_walkable() && *c != Cell::Broken;
+            let distances = walk_distances(&map.grid, topology, Position { x: 0, y: 0 });
+            assert!(map.grid.iter().enumerate().all(|(y, row)| row.iter().enumerate()
+                .all(|(x, cell)| !open(cell) || distances[y][x].is_some())), "seed {}: not connected", seed);
+            let spawn_distances = spawn_distances_to_cannonballs(&map, topology);
+            assert!(spawn_distances.iter().all(|d| *d == spawn_distances[0]), "seed {}: unfair distances {:?}", seed, spawn_distances);
+        }
+    }
+}
+
+#[test]
+fn test_bundled_maps_are_fair_to_every_spawn() {
+    for map in bundled_maps() {
+        let distances = spawn_distances_to_cannonballs(&map, Topology::Square);
+        assert!(distances.iter().all(|d| d.is_some() && *d == distances[0]), "{}: unfair distances {:?}", map.name, distances);
+    }
+}
+
+#[test]
+fn test_generated_map_is_offered_in_the_vote() {
+    assert!(map_names().contains(&GENERATED_MAP.to_string()));
+    assert_eq!(map_by_name(GENERATED_MAP, 7, 7, Topology::Square, 3), Some(generate_map(7, 7, Topology::Square, 3)));
+    assert_eq!(map_by_name("Donut", 7, 7, Topology::Square, 3), bundled_map("Donut"));
+    assert_eq!(map_by_name("Nowhere", 7, 7, Topology::Square, 3), None);
+}
 
-    #[test]
-    fn test_player_does_not_spawn_on_object() {
-        let mut grid = create_grid(5, 5);
-        let mut players = vec![];
-        let mut cannonballs = vec![
-            Cannonball { pos: Position { x: 1, y: 1 } },
-            Cannonball { pos: Position { x: 2, y: 2 } },
-        ];
+/// Players of a new game with the given spawn strategy.
+fn spawned(strategy: SpawnStrategy, infos: Vec<PlayerInfo>, seed: u64) -> Vec<Position> {
+    let rules = GameRules { spawn_strategy: strategy, ..GameRules::default() };
+    GameState::new(rules, infos, GameMode::Classic, seed).players.iter().map(|p| p.pos).collect()
+}
 
-        for id in 0..10 {
-            if let Some(p) = spawn_random_player(&grid, &players, id) {
-                // Player should not spawn on a cannonball.
-                assert!(!cannonballs.iter().any(|c| c.pos == p.pos));
-                players.push(p);
+#[test]
+fn test_max_min_spawns_players_far_apart() {
+    for seed in 0..20 {
+        let spawns = spawned(SpawnStrategy::MaxMin, player_infos(2), seed);
+        // The second player takes one of the tiles farthest from the first.
+        let farthest = (0..5)
+            .flat_map(|y| (0..5).map(move |x| Position { x, y }))
+            .map(|pos| DistanceMetric::Manhattan.distance(spawns[0], pos))
+            .max()
+            .unwrap();
+        assert_eq!(DistanceMetric::Manhattan.distance(spawns[0], spawns[1]), farthest, "seed {}", seed);
+    }
+}
+
+#[test]
+fn test_corner_spawns_are_symmetric() {
+    let spawns = spawned(SpawnStrategy::Corners, player_infos(4), 7);
+    assert_eq!(spawns, vec![
+        Position { x: 0, y: 0 },
+        Position { x: 4, y: 4 },
+        Position { x: 4, y: 0 },
+        Position { x: 0, y: 4 },
+    ]);
+}
+
+#[test]
+fn test_draft_spawns_on_picks_or_falls_back() {
+    let mut infos = player_infos(3);
+    infos[0].spawn_pick = Some(Position { x: 2, y: 2 });
+    // Already taken by player 1: falls back to max-min.
+    infos[1].spawn_pick = Some(Position { x: 2, y: 2 });
+    infos[2].spawn_pick = Some(Position { x: 1, y: 3 });
+    let spawns = spawned(SpawnStrategy::Draft, infos, 7);
+    assert_eq!(spawns[0], Position { x: 2, y: 2 });
+    assert_eq!(DistanceMetric::Manhattan.distance(spawns[0], spawns[1]), 4);
+    assert_eq!(spawns[2], Position { x: 1, y: 3 });
+}
+
+#[test]
+fn test_spawn_strategy_is_selectable_per_mode() {
+    let mut rules = GameRules::default();
+    rules.mode_spawn_strategies.insert(GameMode::Teams, SpawnStrategy::Corners);
+    assert_eq!(mode_rules(GameMode::Teams).spawn_strategy(&rules), SpawnStrategy::Corners);
+    assert_eq!(mode_rules(GameMode::Classic).spawn_strategy(&rules), SpawnStrategy::MaxMin);
+}
+
+#[test]
+fn test_cannonballs_spawn_away_from_players() {
+    let grid = generate_grid(5, 5);
+    let players = vec![Player::new(1, Position { x: 2, y: 2 }, "player1".to_string())];
+    let mut rng = GameRng::new(5);
+    let cannonballs = spawn_random_cannonballs(&grid, &players, &[], &[], 100, Topology::Square, 3, &mut rng);
+    assert!(!cannonballs.is_empty());
+    assert!(cannonballs.iter().all(|c| DistanceMetric::Manhattan.distance(c.pos, players[0].pos) >= 3));
+
+    // Eliminated players do not keep cannonballs away (but still occupy their tile).
+    let mut dead = players.clone();
+    dead[0].is_alive = false;
+    let cannonballs = spawn_random_cannonballs(&grid, &dead, &[], &[], 100, Topology::Square, 3, &mut rng);
+    assert_eq!(cannonballs.len(), 24);
+}
+
+#[test]
+fn test_diagonal_moves_need_eight_way_topology() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    let diagonal = PlayerAction::Move(Direction::DownRight);
+    assert_eq!(state.validate_action(1, &diagonal), Err(ActionError::InvalidDirection));
+
+    state.rules.topology = Topology::EightWay;
+    assert_eq!(state.validate_action(1, &PlayerAction::Move(Direction::UpLeft)), Err(ActionError::OutOfBounds));
+    let (state, _) = state.step(&[(1, diagonal)]);
+    assert_eq!(state.players[0].pos, Position { x: 1, y: 1 });
+}
+
+#[test]
+fn test_hex_topology_neighbours_and_distance() {
+    let hex = Topology::Hex;
+    assert!(!hex.allows(Direction::Up));
+    assert_eq!(hex.neighbours(Position { x: 2, y: 2 }, 5, 5), vec![
+        Position { x: 1, y: 2 },
+        Position { x: 3, y: 2 },
+        Position { x: 1, y: 1 },
+        Position { x: 2, y: 1 },
+        Position { x: 1, y: 3 },
+        Position { x: 2, y: 3 },
+    ]);
+    // Odd rows lean right.
+    assert_eq!(hex.neighbour(Position { x: 2, y: 1 }, Direction::UpRight, 5, 5), Some(Position { x: 3, y: 0 }));
+    assert_eq!(hex.neighbour(Position { x: 0, y: 0 }, Direction::UpLeft, 5, 5), None);
+
+    for pos in hex.neighbours(Position { x: 2, y: 1 }, 5, 5) {
+        assert_eq!(hex.distance(Position { x: 2, y: 1 }, pos), 1);
+    }
+    assert_eq!(hex.distance(Position { x: 0, y: 0 }, Position { x: 4, y: 4 }), 6);
+    assert_eq!(Topology::Square.distance(Position { x: 0, y: 0 }, Position { x: 4, y: 4 }), 8);
+    assert_eq!(Topology::EightWay.distance(Position { x: 0, y: 0 }, Position { x: 4, y: 4 }), 4);
+
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.rules.topology = Topology::Hex;
+    assert_eq!(state.validate_action(1, &PlayerAction::Move(Direction::Down)), Err(ActionError::InvalidDirection));
+    let (state, _) = state.step(&[(1, PlayerAction::Move(Direction::DownRight))]);
+    assert_eq!(state.players[0].pos, Position { x: 0, y: 1 });
+}
+
+#[test]
+fn test_direction_toward_follows_topology() {
+    let origin = Position { x: 0, y: 0 };
+    assert_eq!(Topology::Square.direction_toward(origin, Position { x: 1, y: 2 }), Some(Direction::Down));
+    assert_eq!(Topology::Square.direction_toward(origin, Position { x: 2, y: 2 }), Some(Direction::Right));
+    assert_eq!(Topology::EightWay.direction_toward(origin, Position { x: 3, y: 3 }), Some(Direction::DownRight));
+    assert_eq!(Topology::Hex.direction_toward(Position { x: 2, y: 2 }, Position { x: 1, y: 0 }), Some(Direction::UpLeft));
+    assert_eq!(Topology::Hex.direction_toward(origin, origin), None);
+}
+
+#[test]
+fn test_blast_shapes_follow_topology() {
+    let target = Position { x: 2, y: 2 };
+    let origin = Position { x: 0, y: 0 };
+    assert_eq!(blast_tiles(BlastShape::Cross, Topology::Hex, target, origin, 5, 5).len(), 7);
+    assert_eq!(blast_tiles(BlastShape::Square, Topology::Hex, target, origin, 5, 5).len(), 7);
+    assert_eq!(blast_tiles(BlastShape::Cross, Topology::EightWay, target, origin, 5, 5).len(), 5);
+    assert_eq!(
+        blast_tiles(BlastShape::Line { length: 3 }, Topology::EightWay, target, origin, 5, 5),
+        vec![target, Position { x: 3, y: 3 }, Position { x: 4, y: 4 }],
+    );
+}
+
+#[test]
+fn test_shot_range_and_line_of_sight_follow_topology() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.rules.topology = Topology::Hex;
+    state.players[0].cannonball_count = 1;
+    state.players[0].pos = Position { x: 2, y: 2 };
+    state.rules.shot_range = Some(ShotRange { metric: DistanceMetric::Manhattan, max: 1 });
+    assert_eq!(legal_targets(&state, 0).len(), 7);
+
+    // Hexagonal steps, not the Manhattan distance of 8 on the grid coordinates.
+    state.players[0].pos = Position { x: 0, y: 0 };
+    state.rules.shot_range = Some(ShotRange { metric: DistanceMetric::Manhattan, max: 6 });
+    assert!(is_legal_target(&state, 0, Position { x: 4, y: 4 }));
+
+    // The straight line from (0, 0) to (1, 2) crosses (0, 1) on hexagons, (1, 1) on squares.
+    let target = Position { x: 1, y: 2 };
+    assert_eq!(Topology::Hex.line_between(Position { x: 0, y: 0 }, target, 5, 5), vec![Position { x: 0, y: 1 }]);
+    state.grid[1][1] = Cell::Wall;
+    assert!(is_legal_target(&state, 0, target));
+    state.grid[1][1] = Cell::Solid;
+    state.grid[1][0] = Cell::Wall;
+    assert_eq!(check_target(&state, 0, target), Err(ActionError::Blocked));
+    state.rules.topology = Topology::Square;
+    assert!(is_legal_target(&state, 0, target));
+
+    // Every step of a hexagonal line is a move to a neighbour.
+    let (from, to) = (Position { x: 0, y: 4 }, Position { x: 4, y: 0 });
+    let mut tiles = vec![from];
+    tiles.extend(Topology::Hex.line_between(from, to, 5, 5));
+    tiles.push(to);
+    assert_eq!(tiles.len(), Topology::Hex.distance(from, to) + 1);
+    assert!(tiles.windows(2).all(|w| Topology::Hex.distance(w[0], w[1]) == 1));
+}
+
+#[test]
+fn test_hex_lines_stay_on_the_board() {
+    // A vertical line between odd rows of the last column runs along the board's edge.
+    let line = Topology::Hex.line_between(Position { x: 6, y: 1 }, Position { x: 6, y: 5 }, 7, 7);
+    assert_eq!(line.len(), 3);
+    assert!(line.iter().all(|p| p.x < 7 && p.y < 7), "{:?}", line);
+
+    for (rows, cols) in [(1, 1), (4, 4), (5, 6), (7, 7)] {
+        let tiles: Vec<Position> = (0..rows)
+            .flat_map(|y| (0..cols).map(move |x| Position { x, y }))
+            .collect();
+        for &from in &tiles {
+            for &to in &tiles {
+                let line = Topology::Hex.line_between(from, to, rows, cols);
+                assert!(line.iter().all(|p| p.x < cols && p.y < rows), "{:?} -> {:?}: {:?}", from, to, line);
             }
         }
     }
-}
\ No newline at end of file
+
+    // Every target of a hex game can be checked without leaving the grid.
+    let mut state = game(GameMode::Classic, player_infos(2), &[(4, 1), (0, 0)]);
+    state.rules.topology = Topology::Hex;
+    state.players[0].cannonball_count = 1;
+    assert!(legal_targets(&state, 0).contains(&Position { x: 4, y: 3 }));
+}
+
+#[test]
+fn test_render_grid_indents_odd_hex_rows() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    let square = render_grid(&state);
+    assert!(square.lines().all(|line| !line.starts_with(' ')));
+    assert!(square.starts_with("P1"));
+
+    state.rules.topology = Topology::Hex;
+    let hex = render_grid(&state);
+    let lines: Vec<&str> = hex.lines().collect();
+    assert_eq!(lines.len(), 5);
+    assert!(lines[0].starts_with("P1"));
+    assert!(lines[1].starts_with(' '));
+    assert!(!lines[2].starts_with(' '));
+}
+
+#[test]
+fn test_legal_actions_follow_topology() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    let moves = |state: &GameState| legal_actions(state, 0).iter()
+        .filter(|a| matches!(a, PlayerAction::Move(_)))
+        .count();
+    // Stay, right and down from the top-left corner.
+    assert_eq!(moves(&state), 3);
+    state.rules.topology = Topology::EightWay;
+    assert_eq!(moves(&state), 4);
+
+    state.players[0].cannonball_count = 1;
+    assert_eq!(legal_actions(&state, 0).len(), 4 + legal_targets(&state, 0).len());
+    state.players[0].is_alive = false;
+    assert!(legal_actions(&state, 0).is_empty());
+}
+
+#[test]
+fn test_agents_choose_valid_deterministic_actions() {
+    let mut state = game(GameMode::Classic, player_infos(3), &[(0, 0), (2, 2), (4, 4)]);
+    state.players[0].cannonball_count = 2;
+    state.players[1].inventory.push(ItemKind::Dash);
+    state.cannonballs.push(Cannonball { pos: Position { x: 1, y: 3 } });
+    for bot in AGENTS {
+        for me in 0..state.players.len() {
+            let action = bot.choose_action(&state, me);
+            assert_eq!(state.validate_action(state.players[me].id, &action), Ok(()), "{:?}", bot.kind());
+            assert_eq!(format!("{:?}", bot.choose_action(&state, me)), format!("{:?}", action));
+        }
+    }
+}
+
+#[test]
+fn test_smart_agents_dodge_incoming_shots() {
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    state.grid[0][1] = Cell::Broken;
+    state.targeted_tiles.push(TargetedTile {
+        x: 0,
+        y: 0,
+        origin: Position { x: 4, y: 4 },
+        team_id: None,
+    });
+    for kind in [AgentKind::Greedy, AgentKind::Search] {
+        let action = agent(kind).choose_action(&state, 0);
+        assert!(matches!(action, PlayerAction::Move(Direction::Down)), "{:?} chose {:?}", kind, action);
+    }
+}
+
+#[test]
+fn test_simulated_games_are_reproducible() {
+    let rules = GameRules::default();
+    let seats = [AgentKind::Greedy, AgentKind::Random];
+    let first = play_game(&rules, GameMode::Classic, &seats, 3, 200);
+    let second = play_game(&rules, GameMode::Classic, &seats, 3, 200);
+    assert!(first.outcome.is_some());
+    assert_eq!(first.outcome, second.outcome);
+    assert_eq!(first.turns, second.turns);
+
+    let capped = play_game(&rules, GameMode::Classic, &seats, 3, 0);
+    assert_eq!(capped.turns, 0);
+    assert!(capped.outcome.is_none());
+}
+
+#[test]
+fn test_mode_report_counts_wins_by_seat() {
+    let seats = [AgentKind::Random, AgentKind::Random, AgentKind::Random];
+    let game = |result: Option<GameResult>, turns| SimulatedGame {
+        outcome: result.map(|result| GameOutcome { result, placements: Vec::new() }),
+        turns,
+    };
+    let mut report = ModeReport::new(GameMode::Teams, &seats);
+    report.record(&game(Some(GameResult::Winner { player_id: 2 }), 10));
+    report.record(&game(Some(GameResult::TeamWinner { team_id: 1, player_ids: vec![1, 3] }), 20));
+    report.record(&game(Some(GameResult::Draw { player_ids: vec![1, 2] }), 5));
+    report.record(&game(None, 25));
+
+    assert_eq!(report.games, 4);
+    assert_eq!(report.wins, vec![1, 1, 1]);
+    assert_eq!(report.draws, 1);
+    assert_eq!(report.unfinished, 1);
+    assert_eq!(report.average_turns(), 15.0);
+    assert!(report.to_string().contains("seat 2 (Random): 25.0% wins"));
+}
+
+#[test]
+fn test_demo_key_bindings() {
+    use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+    use crate::game::demo::input::{Command, command_for};
+
+    let press = |code| command_for(KeyEvent::new(code, KeyModifiers::NONE));
+    assert_eq!(press(KeyCode::Up), Some(Command::Move(Direction::Up)));
+    assert_eq!(command_for(KeyEvent::new(KeyCode::Char('D'), KeyModifiers::SHIFT)), Some(Command::Move(Direction::Right)));
+    assert_eq!(press(KeyCode::Char('z')), Some(Command::Move(Direction::DownLeft)));
+    assert_eq!(press(KeyCode::Char(' ')), Some(Command::Move(Direction::Stay)));
+    assert_eq!(press(KeyCode::Char('f')), Some(Command::Aim));
+    assert_eq!(press(KeyCode::Char('g')), Some(Command::Dash));
+    assert_eq!(press(KeyCode::Char('r')), Some(Command::Repair));
+    assert_eq!(press(KeyCode::Char('x')), Some(Command::Freeze));
+    assert_eq!(press(KeyCode::Char('3')), Some(Command::Player(3)));
+    assert_eq!(press(KeyCode::Char('0')), None);
+    assert_eq!(press(KeyCode::Char('y')), None);
+    assert_eq!(command_for(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)), Some(Command::Quit));
+    let release = KeyEvent::new_with_kind(KeyCode::Up, KeyModifiers::NONE, KeyEventKind::Release);
+    assert_eq!(command_for(release), None);
+}
+
+#[test]
+fn test_demo_render_shows_last_shots_and_cursor() {
+    use crate::game::demo::game_loop::{event_text, shot_tiles};
+    use crate::game::demo::render::render_grid;
+
+    let mut state = game(GameMode::Classic, player_infos(2), &[(0, 0), (4, 4)]);
+    let plain = render_grid(&state, &[], None);
+    assert!(plain.contains("P1") && plain.contains("P2"));
+    // Red background (ANSI 256-color code 9) and reverse video.
+    assert!(!plain.contains("48;5;9m"));
+    assert!(!plain.contains("\x1b[7m"));
+
+    // The shots of the last turn are highlighted once it is resolved.
+    state.players[0].cannonball_count = 1;
+    let (state, events) = state.step(&[(1, PlayerAction::Shoot { x: 2, y: 2 })]);
+    let shots = shot_tiles(&events);
+    assert_eq!(shots, vec![Position { x: 2, y: 2 }]);
+    assert_eq!(event_text(&events[0]), "P1 shot at (2, 2).");
+    let aimed = render_grid(&state, &shots, Some(Position { x: 1, y: 1 }));
+    assert!(aimed.contains("48;5;9m"));
+    assert!(aimed.contains("\x1b[7m"));
+    assert_eq!(aimed.lines().count(), 5);
+}
+
+/// Record a whole bot game on the first bundled map.
+fn recorded_game(seed: u64) -> Replay {
+    let rules = GameRules::default();
+    let seats = [AgentKind::Greedy, AgentKind::Random];
+    let players = bot_infos(&seats, &rules, seed);
+    let map_name = map_names()[0].clone();
+    let map = map_by_name(&map_name, rules.grid_rows, rules.grid_cols, rules.topology, seed).unwrap();
+    let mut state = GameState::new_on_map(rules, players.clone(), GameMode::Classic, &map, seed);
+    let mut replay = Replay::new(&state, players, Some(map_name));
+    while state.outcome().is_none() && state.turn <= 200 {
+        let actions: Vec<(u8, PlayerAction)> = state.players.iter().enumerate()
+            .filter(|(_, p)| p.is_alive)
+            .map(|(i, p)| (p.id, agent(seats[i]).choose_action(&state, i)))
+            .collect();
+        state = state.step(&actions).0;
+        replay.record_turn(&actions, &state);
+    }
+    replay
+}
+
+#[test]
+fn test_replay_verifies_after_save_and_load() {
+    let replay = recorded_game(5);
+    assert!(replay.outcome.is_some());
+    let path = std::env::temp_dir().join(format!("lava-grid-replay-{}.json", std::process::id()));
+    replay.save(&path).unwrap();
+    let loaded = Replay::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let last = loaded.verify().unwrap();
+    assert_eq!(last.outcome(), replay.outcome);
+    assert_eq!(last.turn as usize, replay.turns.len() + 1);
+}
+
+#[test]
+fn test_replay_reports_first_diverging_turn() {
+    let recorded = recorded_game(5);
+
+    let mut tampered = recorded.clone();
+    tampered.turns[2].state_hash ^= 1;
+    let expected = tampered.turns[2].state_hash;
+    assert_eq!(
+        tampered.verify().unwrap_err(),
+        ReplayError::StateMismatch { turn: 3, expected, actual: recorded.turns[2].state_hash },
+    );
+
+    let mut reseeded = recorded.clone();
+    reseeded.seed += 1;
+    assert!(matches!(reseeded.verify(), Err(ReplayError::StateMismatch { turn: 0, .. })));
+
+    let mut unknown = recorded;
+    unknown.map = Some("Nowhere".to_string());
+    assert_eq!(unknown.verify().unwrap_err(), ReplayError::UnknownMap { name: "Nowhere".to_string() });
+}
+
+#[test]
+fn test_mode_choice_ends_once_every_mode_vote_is_in() {
+    use std::collections::HashMap;
+    use crate::server::game_session::mode_choice::ModeChoice;
+
+    let (players, spectators) = (HashMap::new(), HashMap::new());
+    let mut choice = ModeChoice::new(2, 1, &GameRules::default());
+    choice.receive_map_vote("a".to_string(), map_names()[0].clone(), &players, &spectators);
+    assert!(!choice.receive_mode_vote("a".to_string(), GameMode::Classic, &players, &spectators));
+    // The second player never votes for a map.
+    assert!(choice.receive_mode_vote("b".to_string(), GameMode::Cracked, &players, &spectators));
+}
+
+#[test]
+fn test_cli_parses_variant_names_and_lists() {
+    assert_eq!(parse_name::<GameMode>(" FogOfWar"), Ok(GameMode::FogOfWar));
+    assert_eq!(parse_list::<AgentKind>("Random, Search"), Ok(vec![AgentKind::Random, AgentKind::Search]));
+    assert_eq!(parse_list::<AgentKind>("Random,Nobody"), Err("unknown value Nobody".to_string()));
+}
+
+#[test]
+fn test_session_submits_bot_actions_each_turn() {
+    use actix::prelude::*;
+    use crate::server::game_session::mode_choice::ModeChoice;
+    use crate::server::game_session::server::{GameSession, GetTurnStatus};
+    use crate::server::matchmaking::types::bot_info;
+
+    let rules = GameRules { mode_choice_duration_secs: 0, turn_duration_secs: 1, ..GameRules::default() };
+    let mut infos = player_infos(1);
+    infos.push(bot_info(AgentKind::Greedy, 2));
+    let mut session = GameSession::new(uuid::Uuid::new_v4(), infos, rules.clone());
+    session.seed = 3;
+    session.mode_choice = ModeChoice::new(1, 3, &rules);
+
+    System::new().block_on(async {
+        let session = session.start();
+        // Nobody votes, so the first turn starts at once. Only the bot acts: the human's
+        // turns time out.
+        for turn in 1..=2 {
+            actix::clock::sleep(std::time::Duration::from_millis(300)).await;
+            let status = session.send(GetTurnStatus).await.unwrap();
+            assert_eq!(status.turn, turn);
+            assert_eq!(status.acted, vec!["bot-2".to_string()]);
+            actix::clock::sleep(std::time::Duration::from_millis(800)).await;
+        }
+    });
+}
+
+#[test]
+fn test_spawn_picks_must_be_spawn_tiles_of_the_chosen_map() {
+    use crate::server::game_session::server::GameSession;
+
+    let map = GameMap {
+        name: "Pads".to_string(),
+        grid: vec![
+            vec![Cell::SpawnPad, Cell::Solid, Cell::Wall],
+            vec![Cell::Solid, Cell::Broken, Cell::Solid],
+            vec![Cell::Solid, Cell::Solid, Cell::SpawnPad],
+        ],
+        cannonballs: Vec::new(),
+    };
+    let mut session = GameSession::new(uuid::Uuid::new_v4(), player_infos(2), GameRules::default());
+    let error = |session: &GameSession, wallet: &str, x: usize, y: usize| {
+        session.spawn_pick_error(&wallet.to_string(), Position { x, y }).map(|(code, _)| code)
+    };
+    // The draft only opens once the map is chosen.
+    assert_eq!(error(&session, "0x1", 0, 0), Some("SPAWN_PICK_CLOSED"));
+
+    session.draft_candidates = Some(GameState::draft_candidates(GameMode::Classic, &map));
+    assert_eq!(error(&session, "0x1", 0, 0), None);
+    assert_eq!(error(&session, "0x9", 0, 0), Some("UNKNOWN_PLAYER"));
+    // Out of bounds, a wall, a hole, and a solid tile off the spawn pads.
+    for (x, y) in [(3, 0), (2, 0), (1, 1), (1, 0)] {
+        assert_eq!(error(&session, "0x1", x, y), Some("SPAWN_INVALID"), "pick ({}, {})", x, y);
+    }
+    session.player_infos[0].spawn_pick = Some(Position { x: 0, y: 0 });
+    assert_eq!(error(&session, "0x2", 0, 0), Some("SPAWN_TAKEN"));
+    assert_eq!(error(&session, "0x2", 2, 2), None);
+}
+
+#[test]
+fn test_session_holds_the_spawn_draft_after_the_mode_choice() {
+    use actix::prelude::*;
+    use crate::server::game_session::mode_choice::ModeChoice;
+    use crate::server::game_session::server::{GameSession, GetTurnStatus};
+
+    let rules = GameRules {
+        mode_choice_duration_secs: 0,
+        spawn_draft_duration_secs: 1,
+        spawn_strategy: SpawnStrategy::Draft,
+        ..GameRules::default()
+    };
+    let mut session = GameSession::new(uuid::Uuid::new_v4(), player_infos(2), rules.clone());
+    session.mode_choice = ModeChoice::new(2, 3, &rules);
+
+    System::new().block_on(async {
+        let session = session.start();
+        // Nobody votes nor picks: the game starts when the draft deadline passes.
+        actix::clock::sleep(std::time::Duration::from_millis(300)).await;
+        assert_eq!(session.send(GetTurnStatus).await.unwrap().turn, 0);
+        actix::clock::sleep(std::time::Duration::from_millis(1000)).await;
+        assert_eq!(session.send(GetTurnStatus).await.unwrap().turn, 1);
+    });
+}
@@ -0,0 +1,286 @@
+//! Board topology.
+//!
+//! A [`Topology`] describes how the tiles of the grid connect to each other: which
+//! directions a player may move in, which tiles neighbour a position, how far apart two
+//! tiles are and where each tile is drawn. Movement, blast shapes, knockback, lava spread
+//! and spawn placement all go through it, so the same grid of cells can be played as a
+//! square board (four or eight directions) or a hexagonal one.
+//!
+//! Hexagonal grids use "odd-r" offset coordinates: tiles are stored in rows like a square
+//! grid, and odd rows are drawn shifted right by half a tile. A hexagon has six
+//! neighbours: left, right and the four diagonals (there is no straight up or down).
+
+use serde::{Serialize, Deserialize};
+
+use crate::game::types::{Direction, Position};
+
+/// How the tiles of the grid connect to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Topology {
+    /// Square tiles, moving in the four cardinal directions.
+    #[default]
+    Square,
+    /// Square tiles, moving in the four cardinal and the four diagonal directions.
+    EightWay,
+    /// Hexagonal tiles in odd-r offset coordinates, moving in six directions.
+    Hex,
+}
+
+const CARDINAL: [Direction; 4] = [Direction::Left, Direction::Right, Direction::Up, Direction::Down];
+
+const EIGHT_WAY: [Direction; 8] = [
+    Direction::Left,
+    Direction::Right,
+    Direction::Up,
+    Direction::Down,
+    Direction::UpLeft,
+    Direction::UpRight,
+    Direction::DownLeft,
+    Direction::DownRight,
+];
+
+const HEX: [Direction; 6] = [
+    Direction::Left,
+    Direction::Right,
+    Direction::UpLeft,
+    Direction::UpRight,
+    Direction::DownLeft,
+    Direction::DownRight,
+];
+
+impl Topology {
+    /// Directions a player may move in (besides staying in place), in a stable order.
+    pub fn directions(self) -> &'static [Direction] {
+        match self {
+            Topology::Square => &CARDINAL,
+            Topology::EightWay => &EIGHT_WAY,
+            Topology::Hex => &HEX,
+        }
+    }
+
+    /// Whether a move in the given direction exists on this board. Staying is always allowed.
+    pub fn allows(self, direction: Direction) -> bool {
+        direction == Direction::Stay || self.directions().contains(&direction)
+    }
+
+    /// The tile next to `pos` in the given direction, or `None` if it lies outside a grid of
+    /// `rows` by `cols` tiles or the direction does not exist on this board.
+    ///
+    /// Staying returns `pos` itself.
+    pub fn neighbour(self, pos: Position, direction: Direction, rows: usize, cols: usize) -> Option<Position> {
+        if !self.allows(direction) {
+            return None;
+        }
+        let (dx, dy) = match (self, direction) {
+            (_, Direction::Stay) => (0, 0),
+            (_, Direction::Left) => (-1, 0),
+            (_, Direction::Right) => (1, 0),
+            (_, Direction::Up) => (0, -1),
+            (_, Direction::Down) => (0, 1),
+            // On odd-r hexagons, diagonal neighbours lean right on odd rows.
+            (Topology::Hex, diagonal) => {
+                let shift = (pos.y % 2) as isize;
+                let dx = match diagonal {
+                    Direction::UpLeft | Direction::DownLeft => shift - 1,
+                    _ => shift,
+                };
+                let dy = match diagonal {
+                    Direction::UpLeft | Direction::UpRight => -1,
+                    _ => 1,
+                };
+                (dx, dy)
+            }
+            (_, Direction::UpLeft) => (-1, -1),
+            (_, Direction::UpRight) => (1, -1),
+            (_, Direction::DownLeft) => (-1, 1),
+            (_, Direction::DownRight) => (1, 1),
+        };
+        let x = pos.x.checked_add_signed(dx).filter(|&x| x < cols)?;
+        let y = pos.y.checked_add_signed(dy).filter(|&y| y < rows)?;
+        Some(Position { x, y })
+    }
+
+    /// Tiles a player standing on `pos` can move to in one step, inside the grid.
+    pub fn neighbours(self, pos: Position, rows: usize, cols: usize) -> Vec<Position> {
+        self.directions().iter()
+            .filter_map(|&direction| self.neighbour(pos, direction, rows, cols))
+            .collect()
+    }
+
+    /// Tiles sharing an edge with `pos`: the four orthogonal tiles on square boards, the six
+    /// neighbours on hexagonal ones.
+    pub fn edge_neighbours(self, pos: Position, rows: usize, cols: usize) -> Vec<Position> {
+        match self {
+            Topology::Square | Topology::EightWay => Topology::Square.neighbours(pos, rows, cols),
+            Topology::Hex => Topology::Hex.neighbours(pos, rows, cols),
+        }
+    }
+
+    /// Tiles touching `pos` by an edge or a corner: the eight surrounding tiles on square
+    /// boards, the six neighbours on hexagonal ones.
+    pub fn touching(self, pos: Position, rows: usize, cols: usize) -> Vec<Position> {
+        match self {
+            Topology::Square | Topology::EightWay => Topology::EightWay.neighbours(pos, rows, cols),
+            Topology::Hex => Topology::Hex.neighbours(pos, rows, cols),
+        }
+    }
+
+    /// Number of moves between two tiles on an open board: Manhattan distance for four
+    /// directions, Chebyshev distance for eight, hexagonal distance for hexagons.
+    pub fn distance(self, a: Position, b: Position) -> usize {
+        match self {
+            Topology::Square => a.x.abs_diff(b.x) + a.y.abs_diff(b.y),
+            Topology::EightWay => a.x.abs_diff(b.x).max(a.y.abs_diff(b.y)),
+            Topology::Hex => {
+                // Convert to axial coordinates, where the distance is half the sum of the
+                // differences along the three hexagonal axes.
+                let (aq, ar) = axial(a);
+                let (bq, br) = axial(b);
+                let (dq, dr) = (aq - bq, ar - br);
+                ((dq.abs() + dr.abs() + (dq + dr).abs()) / 2) as usize
+            }
+        }
+    }
+
+    /// Tiles crossed by a straight line from the centre of `from` to the centre of `to`,
+    /// both ends excluded: a Bresenham line on square boards, the hexagons nearest to evenly
+    /// spaced points of the line on hexagonal ones. Every tile lies inside a grid of `rows`
+    /// by `cols` tiles holding both ends.
+    pub fn line_between(self, from: Position, to: Position, rows: usize, cols: usize) -> Vec<Position> {
+        match self {
+            Topology::Square | Topology::EightWay => square_line(from, to),
+            Topology::Hex => hex_line(from, to, rows, cols),
+        }
+    }
+
+    /// The move direction pointing closest to `to` as seen from `from`, or `None` when both
+    /// tiles are the same. Ties go to the first direction of [`Topology::directions`], so on
+    /// square boards the horizontal direction wins a perfect diagonal.
+    pub fn direction_toward(self, from: Position, to: Position) -> Option<Direction> {
+        let (fx, fy) = self.centre(from);
+        let (tx, ty) = self.centre(to);
+        let (vx, vy) = (tx - fx, ty - fy);
+        if vx == 0.0 && vy == 0.0 {
+            return None;
+        }
+
+        // Compare the cosine of the angle between the target and each direction, measured
+        // from an inner tile so that every direction exists.
+        let probe = Position { x: 2, y: 2 };
+        let (px, py) = self.centre(probe);
+        let mut best: Option<(Direction, f64)> = None;
+        for &direction in self.directions() {
+            let Some(next) = self.neighbour(probe, direction, 5, 5) else {
+                continue;
+            };
+            let (nx, ny) = self.centre(next);
+            let (dx, dy) = (nx - px, ny - py);
+            let score = (vx * dx + vy * dy) / (dx * dx + dy * dy).sqrt();
+            if best.is_none_or(|(_, s)| score > s) {
+                best = Some((direction, score));
+            }
+        }
+        best.map(|(direction, _)| direction)
+    }
+
+    /// Centre of a tile on the board, in tile widths. Odd hexagonal rows are shifted right
+    /// by half a tile and hexagonal rows overlap vertically.
+    pub fn centre(self, pos: Position) -> (f64, f64) {
+        match self {
+            Topology::Square | Topology::EightWay => (pos.x as f64, pos.y as f64),
+            Topology::Hex => (
+                pos.x as f64 + 0.5 * (pos.y % 2) as f64,
+                pos.y as f64 * 3f64.sqrt() / 2.0,
+            ),
+        }
+    }
+
+    /// Number of half tiles a row is indented by when the board is drawn row by row.
+    pub fn row_indent(self, y: usize) -> usize {
+        match self {
+            Topology::Square | Topology::EightWay => 0,
+            Topology::Hex => y % 2,
+        }
+    }
+}
+
+/// Axial coordinates `(q, r)` of an odd-r hexagonal tile.
+fn axial(pos: Position) -> (isize, isize) {
+    let r = pos.y as isize;
+    (pos.x as isize - (r - (r & 1)) / 2, r)
+}
+
+/// Bresenham line between two square tiles, both ends excluded.
+fn square_line(from: Position, to: Position) -> Vec<Position> {
+    let (mut x, mut y) = (from.x as isize, from.y as isize);
+    let (x1, y1) = (to.x as isize, to.y as isize);
+    let dx = (x1 - x).abs();
+    let dy = -(y1 - y).abs();
+    let sx = if x < x1 { 1 } else { -1 };
+    let sy = if y < y1 { 1 } else { -1 };
+    let mut err = dx + dy;
+
+    let mut tiles = Vec::new();
+    while (x, y) != (x1, y1) {
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+        if (x, y) != (x1, y1) {
+            tiles.push(Position { x: x as usize, y: y as usize });
+        }
+    }
+    tiles
+}
+
+/// Line between two hexagonal tiles, both ends excluded: one tile per step of the
+/// hexagonal distance, rounding evenly spaced points of the line in cube coordinates.
+///
+/// A line running along the edge between two hexagons is nudged to one side so that ties
+/// always round the same way. Along the edge of the board, that side may lie outside the
+/// grid, so the line is then nudged to the other side.
+fn hex_line(from: Position, to: Position, rows: usize, cols: usize) -> Vec<Position> {
+    let inside = |line: &[(isize, isize)]| line.iter()
+        .all(|&(x, y)| (0..cols as isize).contains(&x) && (0..rows as isize).contains(&y));
+    let mut line = nudged_hex_line(from, to, 1e-6);
+    if !inside(&line) {
+        line = nudged_hex_line(from, to, -1e-6);
+    }
+    line.into_iter()
+        .filter(|&(x, y)| (0..cols as isize).contains(&x) && (0..rows as isize).contains(&y))
+        .map(|(x, y)| Position { x: x as usize, y: y as usize })
+        .collect()
+}
+
+/// Offset coordinates of the tiles of a hexagonal line, with both ends nudged by `nudge`
+/// along the cube axes.
+fn nudged_hex_line(from: Position, to: Position, nudge: f64) -> Vec<(isize, isize)> {
+    let steps = Topology::Hex.distance(from, to);
+    let (aq, ar) = axial(from);
+    let (bq, br) = axial(to);
+    let (aq, ar) = (aq as f64 + nudge, ar as f64 + 2.0 * nudge);
+    let (bq, br) = (bq as f64 + nudge, br as f64 + 2.0 * nudge);
+
+    (1..steps)
+        .map(|i| {
+            let t = i as f64 / steps as f64;
+            let q = aq + (bq - aq) * t;
+            let r = ar + (br - ar) * t;
+            let s = -q - r;
+            let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+            let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+            if dq > dr && dq > ds {
+                rq = -rr - rs;
+            } else if dr > ds {
+                rr = -rq - rs;
+            }
+            let (q, r) = (rq as isize, rr as isize);
+            (q + (r - (r & 1)) / 2, r)
+        })
+        .collect()
+}
@@ -5,13 +5,25 @@
 
 use serde::{Serialize, Deserialize};
 
+use crate::game::topology::Topology;
+
 /// Available game modes.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum GameMode {
     /// Classic mode: standard rules.
     Classic,
     /// Cracked mode: special rules (see game logic).
     Cracked,
+    /// Shrinking ring: the outer ring of the grid crumbles on a schedule.
+    ShrinkingRing,
+    /// Spreading lava: broken tiles spread to their neighbours.
+    SpreadingLava,
+    /// Teams: classic tile breaking, the last team standing wins.
+    Teams,
+    /// King of the hill: players score by holding the hill alone, best score wins.
+    KingOfTheHill,
+    /// Fog of war: classic tile breaking, players only see the tiles around them.
+    FogOfWar,
 }
 
 /// Position on the grid.
@@ -24,12 +36,19 @@ pub struct Position {
 }
 
 /// Possible movement directions for a player.
+///
+/// Which of them exist depends on the board's [`Topology`](crate::game::topology::Topology):
+/// diagonals need eight-way movement or hexagons, and hexagons have no straight up or down.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Direction {
     Up,
     Down,
     Left,
     Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
     /// No movement.
     Stay,
 }
@@ -43,6 +62,35 @@ pub enum Cell {
     Cracked,
     /// Broken tile (cannot be walked on, lethal).
     Broken,
+    /// Ice tile: a player moving onto it keeps sliding in the same direction until they
+    /// reach a non-ice tile (or are stopped by a wall or the grid edge).
+    Ice,
+    /// Indestructible wall: blocks movement and shots.
+    Wall,
+    /// Teleporter: a player ending their move on it is sent to the other teleporter of
+    /// the same channel.
+    Teleporter { channel: u8 },
+    /// Unbreakable tile of the hill, where players score in king of the hill.
+    Hill,
+    /// Unbreakable tile where players spawn.
+    SpawnPad,
+}
+
+impl Cell {
+    /// Whether a player can stand on this tile (it may still be lethal).
+    pub fn is_walkable(self) -> bool {
+        self != Cell::Wall
+    }
+
+    /// Whether this tile can be broken by the mode's tile breaking or by a cannonball.
+    pub fn is_breakable(self) -> bool {
+        matches!(self, Cell::Solid | Cell::Cracked | Cell::Ice | Cell::Teleporter { .. })
+    }
+
+    /// Whether this tile is intact and can be picked to break or crack at the end of a turn.
+    pub fn is_intact(self) -> bool {
+        matches!(self, Cell::Solid | Cell::Ice | Cell::Teleporter { .. })
+    }
 }
 
 /// Player representation in the game.
@@ -58,6 +106,18 @@ pub struct Player {
     pub cannonball_count: u32,
     /// Whether the player is alive.
     pub is_alive: bool,
+    /// Turn during which the player was eliminated (`None` while alive).
+    pub eliminated_turn: Option<u32>,
+    /// Items the player holds, in pickup order.
+    pub inventory: Vec<ItemKind>,
+    /// Whether an armed shield will absorb the player's next fall.
+    pub shielded: bool,
+    /// Whether the player's next action will be skipped.
+    pub frozen: bool,
+    /// Team of the player in team modes (`None` in free-for-all).
+    pub team_id: Option<u8>,
+    /// Points scored in scoring modes.
+    pub score: u32,
 }
 
 impl Player {
@@ -69,6 +129,12 @@ impl Player {
             username,
             cannonball_count: 0,
             is_alive: true,
+            eliminated_turn: None,
+            inventory: Vec::new(),
+            shielded: false,
+            frozen: false,
+            team_id: None,
+            score: 0,
         }
     }
 }
@@ -80,11 +146,155 @@ pub struct Cannonball {
     pub pos: Position,
 }
 
+/// Kinds of power-up items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ItemKind {
+    /// Once used, absorbs the player's next fall onto a broken tile.
+    Shield,
+    /// Move two tiles in one direction.
+    Dash,
+    /// Restore an adjacent broken tile to solid.
+    RepairKit,
+    /// Skip an opponent's next action.
+    Freeze,
+}
+
+/// An item lying on the grid, waiting to be picked up.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Item {
+    /// Position of the item.
+    pub pos: Position,
+    /// Kind of the item.
+    pub kind: ItemKind,
+}
+
+/// How a player uses an item from their inventory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ItemUse {
+    /// Arm a shield.
+    Shield,
+    /// Dash two tiles in a direction.
+    Dash(Direction),
+    /// Repair the broken tile at `(x, y)`.
+    RepairKit { x: usize, y: usize },
+    /// Freeze the player with the given ID.
+    Freeze { player_id: u8 },
+}
+
+impl ItemUse {
+    /// Kind of the item consumed by this use.
+    pub fn kind(self) -> ItemKind {
+        match self {
+            ItemUse::Shield => ItemKind::Shield,
+            ItemUse::Dash(_) => ItemKind::Dash,
+            ItemUse::RepairKit { .. } => ItemKind::RepairKit,
+            ItemUse::Freeze { .. } => ItemKind::Freeze,
+        }
+    }
+}
+
 /// A tile targeted by a cannonball shot (to be broken at end of turn).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TargetedTile {
     /// X coordinate of the targeted tile.
     pub x: usize,
     /// Y coordinate of the targeted tile.
     pub y: usize,
-}
\ No newline at end of file
+    /// Position of the shooter when the shot was fired (orients line blasts and knockbacks).
+    pub origin: Position,
+    /// Team of the shooter, whose members the blast spares without friendly fire.
+    pub team_id: Option<u8>,
+}
+
+/// How distances are measured on the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DistanceMetric {
+    /// Sum of the horizontal and vertical distances (diamond-shaped range).
+    Manhattan,
+    /// Largest of the horizontal and vertical distances (square-shaped range).
+    Chebyshev,
+}
+
+impl DistanceMetric {
+    /// Distance between two positions.
+    pub fn distance(self, a: Position, b: Position) -> usize {
+        let dx = a.x.abs_diff(b.x);
+        let dy = a.y.abs_diff(b.y);
+        match self {
+            DistanceMetric::Manhattan => dx + dy,
+            DistanceMetric::Chebyshev => dx.max(dy),
+        }
+    }
+}
+
+/// Maximum distance at which a player can shoot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShotRange {
+    /// Metric used to measure the distance from the shooter on square boards. Hexagonal
+    /// boards always count hexagonal steps.
+    pub metric: DistanceMetric,
+    /// Largest allowed distance (inclusive).
+    pub max: usize,
+}
+
+impl ShotRange {
+    /// Distance from `a` to `b` as measured by this range on the given board.
+    pub fn distance(self, topology: Topology, a: Position, b: Position) -> usize {
+        match topology {
+            Topology::Square | Topology::EightWay => self.metric.distance(a, b),
+            Topology::Hex => topology.distance(a, b),
+        }
+    }
+}
+
+/// Tiles broken by a cannonball around its target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "shape", content = "data")]
+pub enum BlastShape {
+    /// Only the targeted tile.
+    Single,
+    /// The targeted tile and the tiles sharing an edge with it (four orthogonal neighbours on
+    /// square boards, six on hexagonal ones).
+    Cross,
+    /// The targeted tile and every tile touching it (the 3x3 square on square boards).
+    Square,
+    /// `length` tiles starting at the target and going away from the shooter.
+    Line { length: usize },
+}
+
+/// How players are placed on the grid at the start of the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpawnStrategy {
+    /// Any free tile, uniformly.
+    Random,
+    /// Each player spawns as far as possible (in moves on the board's topology) from the
+    /// players already placed.
+    MaxMin,
+    /// Players spawn in the corners, opposite corners first; beyond four players, like
+    /// `MaxMin`.
+    Corners,
+    /// Players spawn on the tile they picked in the pre-game phase, in player order; a
+    /// missing or unavailable pick falls back to `MaxMin`.
+    Draft,
+}
+
+/// What happens to a player standing in a blast.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HitEffect {
+    /// The player falls with the broken tile and is eliminated.
+    Eliminate,
+    /// The player is pushed one tile away from the blast center before the tiles break,
+    /// and only survives if they land on a tile that stays solid.
+    Knockback,
+}
+
+/// Built-in bot players (see [`crate::game::agents`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AgentKind {
+    /// Any valid action, at random.
+    Random,
+    /// The action leading to the best state after one turn.
+    Greedy,
+    /// The action with the best outcome over a few simulated turns.
+    Search,
+}
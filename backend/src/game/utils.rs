@@ -1,17 +1,133 @@
 //! Game utility functions.
 //!
-//! This module provides helper functions for game state updates.
+//! This module provides helper functions for game state updates, such as resolving
+//! cannonball blasts at the end of a turn.
 
-use super::types::Cell;
+use super::types::{Cell, Position, BlastShape, HitEffect, TargetedTile, Player};
+use super::topology::Topology;
 use super::state::GameState;
+use super::events::GameEvent;
+use super::grid::{remove_cannonball_at, remove_item_at};
+
+/// Compute the tiles covered by a blast on `target` fired from `origin`, clipped to the grid.
+///
+/// Neighbouring tiles and the direction of a line follow the board's [`Topology`]. Tiles are
+/// returned in a stable order without duplicates.
+pub fn blast_tiles(shape: BlastShape, topology: Topology, target: Position, origin: Position, rows: usize, cols: usize) -> Vec<Position> {
+    let mut tiles = vec![target];
+    match shape {
+        BlastShape::Single => {}
+        BlastShape::Cross => tiles.extend(topology.edge_neighbours(target, rows, cols)),
+        BlastShape::Square => tiles.extend(topology.touching(target, rows, cols)),
+        BlastShape::Line { length } => {
+            if let Some(direction) = topology.direction_toward(origin, target) {
+                let mut current = target;
+                for _ in 1..length.max(1) {
+                    let Some(next) = topology.neighbour(current, direction, rows, cols) else {
+                        break;
+                    };
+                    tiles.push(next);
+                    current = next;
+                }
+            }
+        }
+    }
+    tiles
+}
 
 /// Resolve all cannonball hits for the current turn.
-/// Breaks all targeted tiles and clears the targeted list.
-pub fn resolve_cannonball_hits(game_state: &mut GameState) {
-    for tile in &game_state.targeted_tiles {
-        // Mark the targeted tile as broken.
-        game_state.grid[tile.y][tile.x] = Cell::Broken;
+///
+/// Every targeted tile blasts the tiles of the rules' [`BlastShape`]. With
+/// [`HitEffect::Knockback`], living players in a blast are first pushed one tile away from
+/// its center (along the shot when they stand on the target). Then all blasted tiles break
+/// (except walls and spawn pads), destroying any cannonball or item lying there, and the targeted
+/// list is cleared. Players left on
+/// a broken tile are eliminated by the per-player rules.
+///
+/// Without friendly fire, a blast neither pushes the shooter's teammates nor breaks the
+/// tiles they stand on (another team's blast still does).
+pub fn resolve_cannonball_hits(game_state: &mut GameState, events: &mut Vec<GameEvent>) {
+    // Take the list of targeted tiles, leaving it empty for the next turn.
+    let targeted_tiles = std::mem::take(&mut game_state.targeted_tiles);
+    let rows = game_state.grid.len();
+    let cols = game_state.grid.first().map_or(0, |row| row.len());
+
+    // Each blast with the tiles it covers, minus those of spared teammates.
+    let blasts: Vec<(&TargetedTile, Vec<Position>)> = targeted_tiles.iter()
+        .map(|tile| {
+            let target = Position { x: tile.x, y: tile.y };
+            let tiles = blast_tiles(game_state.rules.blast_shape, game_state.rules.topology, target, tile.origin, rows, cols)
+                .into_iter()
+                .filter(|&pos| !game_state.players.iter().any(|p| p.pos == pos && spares(game_state, tile, p)))
+                .collect();
+            (tile, tiles)
+        })
+        .collect();
+
+    if game_state.rules.hit_effect == HitEffect::Knockback {
+        knock_back_players(game_state, &blasts, events);
+    }
+
+    for (_, tiles) in &blasts {
+        for &pos in tiles {
+            remove_cannonball_at(game_state, pos, events);
+            remove_item_at(game_state, pos, events);
+            // Mark the blasted tile as broken (walls and spawn pads resist).
+            let cell = game_state.grid[pos.y][pos.x];
+            if cell.is_breakable() {
+                game_state.grid[pos.y][pos.x] = Cell::Broken;
+                events.push(GameEvent::TileBroken { pos });
+            }
+        }
+    }
+}
+
+/// Whether a blast spares this player: friendly fire is off and the player is a living
+/// teammate of the shooter.
+fn spares(game_state: &GameState, tile: &TargetedTile, player: &Player) -> bool {
+    !game_state.rules.friendly_fire
+        && player.is_alive
+        && player.team_id.is_some()
+        && player.team_id == tile.team_id
+}
+
+/// Push every living player standing in a blast one tile away from its center.
+///
+/// Pushes are computed from the positions before any of them happens, so their order does
+/// not matter. A push is cancelled (the player stays in the blast) when it would leave the
+/// grid, hit a wall, or land on a tile occupied by another player or targeted by another push.
+fn knock_back_players(game_state: &mut GameState, blasts: &[(&TargetedTile, Vec<Position>)], events: &mut Vec<GameEvent>) {
+    let rows = game_state.grid.len();
+    let cols = game_state.grid.first().map_or(0, |row| row.len());
+    let topology = game_state.rules.topology;
+
+    let pushes: Vec<(usize, Position)> = game_state.players.iter().enumerate()
+        .filter(|(_, p)| p.is_alive)
+        .filter_map(|(i, player)| {
+            let (tile, _) = blasts.iter().find(|(_, tiles)| tiles.contains(&player.pos))?;
+            let center = Position { x: tile.x, y: tile.y };
+            let direction = if player.pos == center {
+                topology.direction_toward(tile.origin, center)
+            } else {
+                topology.direction_toward(center, player.pos)
+            }?;
+            let to = topology.neighbour(player.pos, direction, rows, cols)?;
+            if !game_state.grid[to.y][to.x].is_walkable() {
+                return None;
+            }
+            Some((i, to))
+        })
+        .collect();
+
+    let start: Vec<Position> = game_state.players.iter().map(|p| p.pos).collect();
+    for &(i, to) in &pushes {
+        let occupied = game_state.players.iter().enumerate().any(|(j, p)| j != i && p.is_alive && start[j] == to);
+        let contested = pushes.iter().any(|&(j, other)| j != i && other == to);
+        if occupied || contested {
+            continue;
+        }
+        let player = &mut game_state.players[i];
+        events.push(GameEvent::PlayerKnockedBack { player_id: player.id, from: Some(player.pos), to });
+        player.pos = to;
     }
-    // Clear the list of targeted tiles for the next turn.
-    game_state.targeted_tiles.clear();
-}
\ No newline at end of file
+}
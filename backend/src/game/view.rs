@@ -0,0 +1,196 @@
+//! Per-recipient views of the game state.
+//!
+//! In modes with a vision radius (fog of war), each player only sees the tiles around
+//! them. [`Vision`] computes what a recipient can see and filters the state, the turn's
+//! events and the shot targets accordingly; spectators (and eliminated players) see
+//! everything. The server sends a [`GameView`] rather than the [`GameState`] itself, which
+//! also keeps the random generator's state away from clients. In modes with a vision
+//! radius, the game seed is likewise held back until the game is over, since it would let
+//! clients replay every random draw.
+
+use std::collections::BTreeMap;
+use serde::{Serialize, Deserialize};
+
+use crate::config::game::GameRules;
+use crate::game::types::{Cell, Player, Cannonball, Item, TargetedTile, GameMode, Position};
+use crate::game::state::GameState;
+use crate::game::events::GameEvent;
+use crate::game::modes::mode_rules;
+
+/// What a single recipient is allowed to see of a [`GameState`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameView {
+    /// 2D grid of cells; `None` for tiles hidden by the fog.
+    pub grid: Vec<Vec<Option<Cell>>>,
+    /// The recipient and every player standing on a visible tile (eliminated players
+    /// always appear).
+    pub players: Vec<Player>,
+    /// Cannonballs on visible tiles.
+    pub cannonballs: Vec<Cannonball>,
+    /// Items on visible tiles.
+    pub items: Vec<Item>,
+    /// Current turn number (starts at 1).
+    pub turn: u32,
+    /// Targeted tiles that are visible.
+    pub targeted_tiles: Vec<TargetedTileView>,
+    /// Current game mode.
+    pub mode: GameMode,
+    /// Rule set of this game.
+    pub rules: GameRules,
+}
+
+/// A visible targeted tile; where the shot came from and the shooter's team are left out
+/// when the shooter is hidden.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TargetedTileView {
+    pub x: usize,
+    pub y: usize,
+    /// Position of the shooter when the shot was fired (`None` when hidden by the fog).
+    pub origin: Option<Position>,
+    /// Team of the shooter (`None` when hidden by the fog, or outside team modes).
+    pub team_id: Option<u8>,
+}
+
+/// Tiles visible to one recipient.
+#[derive(Debug, Clone)]
+pub struct Vision {
+    /// Recipient's player ID (`None` for spectators).
+    viewer: Option<u8>,
+    /// Visibility of each tile, or `None` when everything is visible.
+    visible: Option<Vec<Vec<bool>>>,
+}
+
+impl Vision {
+    /// Vision of the given player (`None` for a spectator) in `game_state`.
+    ///
+    /// Everything is visible in modes without a vision radius, to spectators, and to
+    /// eliminated or unknown players. Otherwise a player sees the tiles within the radius
+    /// (in moves on the board's topology) of their position.
+    pub fn of(game_state: &GameState, viewer: Option<u8>) -> Self {
+        let radius = mode_rules(game_state.mode).vision_radius(&game_state.rules);
+        let position = viewer
+            .and_then(|id| game_state.players.iter().find(|p| p.id == id))
+            .filter(|p| p.is_alive)
+            .map(|p| p.pos);
+        let visible = match (radius, position) {
+            (Some(radius), Some(center)) => Some(
+                game_state.grid.iter().enumerate()
+                    .map(|(y, row)| (0..row.len())
+                        .map(|x| game_state.rules.topology.distance(center, Position { x, y }) <= radius)
+                        .collect())
+                    .collect(),
+            ),
+            _ => None,
+        };
+        Self { viewer, visible }
+    }
+
+    /// Whether the tile at `pos` is visible.
+    pub fn sees(&self, pos: Position) -> bool {
+        match &self.visible {
+            None => true,
+            Some(visible) => visible.get(pos.y).and_then(|row| row.get(pos.x)).copied().unwrap_or(false),
+        }
+    }
+
+    /// Whether this player appears in the view.
+    fn sees_player(&self, player: &Player) -> bool {
+        Some(player.id) == self.viewer || !player.is_alive || self.sees(player.pos)
+    }
+
+    /// The part of `game_state` this recipient can see.
+    pub fn view(&self, game_state: &GameState) -> GameView {
+        GameView {
+            grid: game_state.grid.iter().enumerate()
+                .map(|(y, row)| row.iter().enumerate()
+                    .map(|(x, cell)| self.sees(Position { x, y }).then_some(*cell))
+                    .collect())
+                .collect(),
+            players: game_state.players.iter().filter(|p| self.sees_player(p)).cloned().collect(),
+            cannonballs: game_state.cannonballs.iter().filter(|c| self.sees(c.pos)).cloned().collect(),
+            items: game_state.items.iter().filter(|i| self.sees(i.pos)).cloned().collect(),
+            turn: game_state.turn,
+            targeted_tiles: game_state.targeted_tiles.iter()
+                .filter(|t| self.sees(Position { x: t.x, y: t.y }))
+                .map(|t| self.targeted_tile(t))
+                .collect(),
+            mode: game_state.mode,
+            rules: game_state.rules.clone(),
+        }
+    }
+
+    /// A visible targeted tile as seen by this recipient: the shooter is hidden when the
+    /// tile they fired from is.
+    fn targeted_tile(&self, tile: &TargetedTile) -> TargetedTileView {
+        let shooter_seen = self.sees(tile.origin);
+        TargetedTileView {
+            x: tile.x,
+            y: tile.y,
+            origin: shooter_seen.then_some(tile.origin),
+            team_id: tile.team_id.filter(|_| shooter_seen),
+        }
+    }
+
+    /// The part of `game_state` this recipient can see, as a state bots can play on:
+    /// hidden tiles are assumed solid, and the players, cannonballs, items and targeted
+    /// tiles on them are left out.
+    pub fn redact(&self, game_state: &GameState) -> GameState {
+        let mut state = game_state.clone();
+        if self.visible.is_none() {
+            return state;
+        }
+        for (y, row) in state.grid.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                if !self.sees(Position { x, y }) {
+                    *cell = Cell::Solid;
+                }
+            }
+        }
+        state.players.retain(|p| self.sees_player(p));
+        state.cannonballs.retain(|c| self.sees(c.pos));
+        state.items.retain(|i| self.sees(i.pos));
+        state.targeted_tiles.retain(|t| self.sees(Position { x: t.x, y: t.y }));
+        state
+    }
+
+    /// Events this recipient can see: those about themselves, those happening on a visible
+    /// tile, and those without a tile whose players are all in view. The tile another
+    /// player left is hidden when it is not visible.
+    pub fn events(&self, game_state: &GameState, events: &[GameEvent]) -> Vec<GameEvent> {
+        if self.visible.is_none() {
+            return events.to_vec();
+        }
+        let in_view = |id: u8| game_state.players.iter().any(|p| p.id == id && self.sees_player(p));
+        events.iter()
+            .filter(|event| {
+                let players = event.players();
+                if self.viewer.is_some_and(|viewer| players.contains(&viewer)) {
+                    return true;
+                }
+                match event.location() {
+                    Some(pos) => self.sees(pos),
+                    None => players.into_iter().all(in_view),
+                }
+            })
+            .map(|event| {
+                let mut event = event.clone();
+                let own = self.viewer.is_some_and(|viewer| event.players().contains(&viewer));
+                if !own && event.origin().is_some_and(|from| !self.sees(from)) {
+                    event.hide_origin();
+                }
+                event
+            })
+            .collect()
+    }
+
+    /// Shot targets this recipient can see: their own under fog, everyone's otherwise.
+    pub fn shot_targets(&self, shot_targets: &BTreeMap<u8, Vec<Position>>) -> BTreeMap<u8, Vec<Position>> {
+        if self.visible.is_none() {
+            return shot_targets.clone();
+        }
+        shot_targets.iter()
+            .filter(|(id, _)| Some(**id) == self.viewer)
+            .map(|(id, targets)| (*id, targets.clone()))
+            .collect()
+    }
+}
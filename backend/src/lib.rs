@@ -0,0 +1,10 @@
+//! Game core and server of the lava grid game.
+//!
+//! The `lava-grid` server binary (`main.rs`) runs the actor-based server; the other
+//! binaries (see `src/bin/`) drive the game core directly, without the actor system, and
+//! parse their arguments with [`cli`].
+
+pub mod config;
+pub mod server;
+pub mod game;
+pub mod cli;
@@ -5,13 +5,10 @@
 
 use actix::Actor;
 use actix_web::{web, App, HttpServer};
+use lava_grid::server;
 use server::matchmaking::server::MatchmakingServer;
 use server::game_session::server::GameSessionManager;
 
-pub mod config;
-mod server;
-mod game;
-
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     // Initialize logger from environment variable (default to info level).
@@ -38,7 +35,7 @@ async fn main() -> std::io::Result<()> {
                     .add(("Access-Control-Allow-Headers", "*"))
             )
             .app_data(state.clone())
-            .configure(crate::server::router::config)
+            .configure(server::router::config)
     })
     .bind(("127.0.0.1", 8080))?
     .run()
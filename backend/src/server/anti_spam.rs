@@ -17,6 +17,12 @@ pub struct AntiSpamState {
     banned_until: Option<Instant>,
 }
 
+impl Default for AntiSpamState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AntiSpamState {
     pub fn new() -> Self {
         Self {
@@ -54,12 +60,10 @@ impl AntiSpamState {
 
     /// Call when sending an error. Returns true if the error should be sent (not suppressed).
     pub fn should_send_error(&mut self, error_code: &str, wallet: &str) -> bool {
-        if let Some(last) = &self.last_error_code {
-            if last == error_code {
-                // Suppress duplicate error
-                warn!("[AntiSpam] Suppressed duplicate error '{}' for wallet={}", error_code, wallet);
-                return false;
-            }
+        if self.last_error_code.as_deref() == Some(error_code) {
+            // Suppress duplicate error
+            warn!("[AntiSpam] Suppressed duplicate error '{}' for wallet={}", error_code, wallet);
+            return false;
         }
         self.last_error_code = Some(error_code.to_string());
         true
@@ -4,15 +4,19 @@
 //! for client-server communication during a game.
 
 use actix::prelude::*;
+use std::collections::BTreeMap;
 use serde::{Serialize, Deserialize};
 use actix::Addr;
 use uuid::Uuid;
 
 use super::session::GameSessionActor;
-use crate::game::types::{Direction, GameMode};
-use crate::game::state::GameState;
+use crate::game::types::{Direction, GameMode, Position, ItemUse};
+use crate::game::state::GameOutcome;
+use crate::game::view::GameView;
+use crate::game::events::GameEvent;
 use crate::server::matchmaking::types::{WalletAddress, PlayerInfo};
 use crate::server::game_session::GameSession;
+use crate::config::game::GameRules;
 
 /// Message to register a pending game (sent by matchmaking when a group is ready).
 #[derive(Message)]
@@ -20,6 +24,8 @@ use crate::server::game_session::GameSession;
 pub struct RegisterPendingGame {
     pub game_id: Uuid,
     pub players: Vec<PlayerInfo>,
+    /// Rule set the game will be played with.
+    pub rules: GameRules,
 }
 
 /// Message to request creation or retrieval of a GameSession for a given game_id.
@@ -40,11 +46,21 @@ pub struct ProcessClientMessage {
     pub addr: Addr<GameSessionActor>,
 }
 
+/// Message sent by a game session to itself with the action chosen by one of its bots.
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct ProcessBotMessage {
+    pub msg: PlayerAction,
+    pub player_id: WalletAddress,
+}
+
 /// Player action sent by the client (move or shoot).
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum PlayerAction {
     Move(Direction),
     Shoot { x: usize, y: usize },
+    /// Use an item from the inventory.
+    UseItem(ItemUse),
 }
 
 /// WebSocket messages sent from client to server during a game session.
@@ -55,8 +71,14 @@ pub enum GameClientWsMessage {
     Move(Direction),
     /// Shoot at a tile.
     Shoot { x: usize, y: usize },
+    /// Use an item from the inventory.
+    UseItem(ItemUse),
     /// Vote for a game mode.
     GameModeVote { mode: GameMode },
+    /// Vote for a map, by name.
+    GameMapVote { map: String },
+    /// Pick a spawn tile for the draft spawn strategy.
+    GameSpawnPick { x: usize, y: usize },
 }
 
 /// Message sent when a player votes for a game mode.
@@ -67,11 +89,31 @@ pub struct GameModeVote {
     pub mode: GameMode,
 }
 
+/// Message sent when a player votes for a map.
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct GameMapVote {
+    pub player_id: WalletAddress,
+    pub map: String,
+    pub addr: Addr<GameSessionActor>,
+}
+
+/// Message sent when a player picks a spawn tile in the pre-game phase.
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct GameSpawnPick {
+    pub player_id: WalletAddress,
+    pub pos: Position,
+    pub addr: Addr<GameSessionActor>,
+}
+
 /// Data sent to all players at the start of the game or when the pre-game phase is refreshed.
 #[derive(Message, Clone, Serialize, Deserialize, Debug)]
 #[rtype(result = "()")]
 pub struct GamePreGameData {
     pub modes: Vec<GameMode>,
+    /// Names of the maps offered in the map vote.
+    pub maps: Vec<String>,
     pub deadline_secs: u64,
     pub players: Vec<PlayerInfo>,
     pub grid_row: usize,
@@ -86,6 +128,42 @@ pub struct GameModeVoteUpdate {
     pub mode: GameMode,
 }
 
+/// Notification sent to all players when a player votes for a map.
+#[derive(Message, Clone, Serialize, Deserialize, Debug)]
+#[rtype(result = "()")]
+pub struct GameMapVoteUpdate {
+    pub player_id: WalletAddress,
+    pub map: String,
+}
+
+/// Notification of the chosen map (`None` for the default open grid), the player whose
+/// vote was picked, and the grid size of the game, taken from the map.
+#[derive(Message, Clone, Serialize, Deserialize, Debug)]
+#[rtype(result = "()")]
+pub struct GameMapChosen {
+    pub map: Option<String>,
+    pub chosen_by: Option<WalletAddress>,
+    pub grid_row: usize,
+    pub grid_col: usize,
+}
+
+/// Notification of the opening of the spawn draft, once the mode and map are chosen, with
+/// the tiles players may pick.
+#[derive(Message, Clone, Serialize, Deserialize, Debug)]
+#[rtype(result = "()")]
+pub struct GameSpawnDraft {
+    pub candidates: Vec<Position>,
+    pub deadline_secs: u64,
+}
+
+/// Notification sent to all players when a player picks a spawn tile.
+#[derive(Message, Clone, Serialize, Deserialize, Debug)]
+#[rtype(result = "()")]
+pub struct GameSpawnPickUpdate {
+    pub player_id: WalletAddress,
+    pub pos: Position,
+}
+
 /// Notification of the chosen mode and the player who was selected to decide.
 #[derive(Message, Clone, Serialize, Deserialize, Debug)]
 #[rtype(result = "()")]
@@ -94,12 +172,38 @@ pub struct GameModeChosen {
     pub chosen_by: WalletAddress,
 }
 
+/// Initial game data sent to all players once the mode is chosen and the game starts.
+#[derive(Message, Clone, Serialize, Deserialize, Debug)]
+#[rtype(result = "()")]
+pub struct GameInit {
+    /// Initial state, as seen by the recipient.
+    pub state: GameView,
+    pub mode: GameMode,
+    /// Seed of the game; with the actions of each turn it is enough to reproduce the game.
+    /// `None` in modes with hidden information, where it is only revealed in [`GameEnded`].
+    pub seed: Option<u64>,
+}
+
 /// Game state update sent to all players after each turn.
 #[derive(Message, Clone, Serialize, Deserialize, Debug)]
 #[rtype(result = "()")]
 pub struct GameStateUpdate {
-    pub state: GameState,
+    /// Current state, as seen by the recipient.
+    pub state: GameView,
     pub turn_duration: u64,
+    /// Events of the last resolved turn, in order (empty before the first turn is resolved).
+    pub events: Vec<GameEvent>,
+    /// Legal shot targets of each living player, keyed by player ID.
+    pub shot_targets: BTreeMap<u8, Vec<Position>>,
+}
+
+/// Final outcome sent to all players and spectators once the game is over.
+#[derive(Message, Clone, Serialize, Deserialize, Debug)]
+#[rtype(result = "()")]
+pub struct GameEnded {
+    pub outcome: GameOutcome,
+    /// Seed of the game, revealed to everyone once it is over.
+    pub seed: u64,
 }
 
 /// Message to kick a session (unicity violation).
@@ -131,12 +235,17 @@ pub struct SendWsTextMessage {
 #[rtype(result = "()")]
 #[serde(tag = "action", content = "data")]
 pub enum GameWsMessage {
-    /// Initial game state and mode.
-    GameInit { state: GameState, mode: GameMode },
-    /// Game state update after a turn.
-    GameStateUpdate { state: GameState, turn_duration: u64 },
-    /// Game ended, with winner.
-    GameEnded { winner: String },
+    /// Initial game state, mode and seed.
+    GameInit { state: GameView, mode: GameMode, seed: Option<u64> },
+    /// Game state update after a turn, with the events of that turn.
+    GameStateUpdate {
+        state: GameView,
+        turn_duration: u64,
+        events: Vec<GameEvent>,
+        shot_targets: BTreeMap<u8, Vec<Position>>,
+    },
+    /// Game ended, with the winner (or draw) and final placements.
+    GameEnded { outcome: GameOutcome, seed: u64 },
     /// Error message.
     Error { message: String },
     /// Session kicked notification.
@@ -147,6 +256,14 @@ pub enum GameWsMessage {
     GameModeVoteUpdate(GameModeVoteUpdate),
     /// Notification of the chosen mode.
     GameModeChosen(GameModeChosen),
+    /// Notification of a map vote.
+    GameMapVoteUpdate(GameMapVoteUpdate),
+    /// Notification of the chosen map.
+    GameMapChosen(GameMapChosen),
+    /// Notification of the opening of the spawn draft.
+    GameSpawnDraft(GameSpawnDraft),
+    /// Notification of a spawn pick.
+    GameSpawnPickUpdate(GameSpawnPickUpdate),
     /// Custom text message.
     CustomMessage { text: String },
 }
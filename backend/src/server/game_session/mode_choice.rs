@@ -1,5 +1,6 @@
-/// Handles the game mode choice phase for a GameSession.
-/// Encapsulates voting, broadcasting pre-game data, and finalization logic.
+//! Handles the game mode choice phase for a GameSession.
+//! Encapsulates voting (for the mode and the map), broadcasting pre-game data, and
+//! finalization logic.
 
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
@@ -8,11 +9,14 @@ use rand::prelude::IteratorRandom;
 use log::info;
 
 use crate::game::types::GameMode;
+use crate::game::rng::{GameRng, RngStream};
+use crate::game::modes::available_modes;
+use crate::game::grid::map_names;
 use crate::server::matchmaking::types::{PlayerInfo, WalletAddress};
 use crate::server::game_session::messages::{
-    GamePreGameData, GameModeVoteUpdate, GameModeChosen,
+    GamePreGameData, GameModeVoteUpdate, GameModeChosen, GameMapVoteUpdate, GameMapChosen,
 };
-use crate::config::game::{MODE_CHOICE_DURATION, GRID_ROW, GRID_COL};
+use crate::config::game::GameRules;
 use crate::server::game_session::session::GameSessionActor;
 
 /// Represents the state and logic for the mode choice phase.
@@ -22,23 +26,48 @@ pub struct ModeChoice {
     pub timer: Option<SpawnHandle>,
     pub chosen_mode: Option<GameMode>,
     pub chosen_by: Option<WalletAddress>,
+    /// Map votes, by map name.
+    pub map_votes: HashMap<WalletAddress, String>,
+    /// Chosen map (`None` for the default open grid).
+    pub chosen_map: Option<String>,
+    /// Player whose map vote was picked.
+    pub chosen_map_by: Option<WalletAddress>,
     pub required_players: usize,
+    /// Generator used to pick the mode, seeded from the game seed.
+    pub rng: GameRng,
+    /// Duration of the mode choice phase.
+    pub duration: Duration,
+    /// Grid size announced in the pre-game data, then the chosen map's once it is loaded.
+    pub grid_row: usize,
+    pub grid_col: usize,
 }
 
 impl ModeChoice {
-    /// Create a new ModeChoice phase for the given number of players.
-    pub fn new(required_players: usize) -> Self {
+    /// Create a new ModeChoice phase for the given number of players and rules.
+    ///
+    /// The mode pick is drawn from a generator seeded with `seed`, so the same votes
+    /// always lead to the same chosen mode.
+    pub fn new(required_players: usize, seed: u64, rules: &GameRules) -> Self {
+        let duration = Duration::from_secs(rules.mode_choice_duration_secs);
         Self {
             votes: HashMap::new(),
-            deadline: Instant::now() + Duration::from_secs(MODE_CHOICE_DURATION),
+            deadline: Instant::now() + duration,
             timer: None,
             chosen_mode: None,
             chosen_by: None,
+            map_votes: HashMap::new(),
+            chosen_map: None,
+            chosen_map_by: None,
             required_players,
+            rng: GameRng::stream(seed, RngStream::ModeChoice),
+            duration,
+            grid_row: rules.grid_rows,
+            grid_col: rules.grid_cols,
         }
     }
 
-    /// Broadcast pre-game data (available modes, deadline, players, grid size) to all sessions.
+    /// Broadcast pre-game data (available modes and maps, deadline, players, grid size) to
+    /// all sessions.
     pub fn broadcast_to_players_pre_game_data(
         &self,
         players: &std::collections::HashMap<WalletAddress, Addr<GameSessionActor>>,
@@ -47,11 +76,12 @@ impl ModeChoice {
     ) {
         let deadline_secs = self.deadline.saturating_duration_since(Instant::now()).as_secs();
         let msg = GamePreGameData {
-            modes: vec![GameMode::Classic, GameMode::Cracked],
+            modes: available_modes(),
+            maps: map_names(),
             deadline_secs,
             players: player_infos.to_vec(),
-            grid_row: GRID_ROW,
-            grid_col: GRID_COL,
+            grid_row: self.grid_row,
+            grid_col: self.grid_col,
         };
         for addr in players.values().chain(spectators.values()) {
             addr.do_send(msg.clone());
@@ -66,7 +96,7 @@ impl ModeChoice {
         players: &std::collections::HashMap<WalletAddress, Addr<GameSessionActor>>,
         spectators: &std::collections::HashMap<WalletAddress, Addr<GameSessionActor>>,
     ) -> bool {
-        self.votes.insert(player_id.clone(), mode.clone());
+        self.votes.insert(player_id.clone(), mode);
         let vote_update = GameModeVoteUpdate {
             player_id,
             mode,
@@ -74,11 +104,34 @@ impl ModeChoice {
         for addr in players.values().chain(spectators.values()) {
             addr.do_send(vote_update.clone());
         }
-        // Return true if all players have voted.
+        self.all_voted()
+    }
+
+    /// Register a map vote from a player and broadcast the update.
+    ///
+    /// The map must be one of the offered maps (see [`map_names`]). Map votes are optional
+    /// and never end the phase: they only count if sent before the last mode vote.
+    pub fn receive_map_vote(
+        &mut self,
+        player_id: WalletAddress,
+        map: String,
+        players: &std::collections::HashMap<WalletAddress, Addr<GameSessionActor>>,
+        spectators: &std::collections::HashMap<WalletAddress, Addr<GameSessionActor>>,
+    ) {
+        self.map_votes.insert(player_id.clone(), map.clone());
+        let vote_update = GameMapVoteUpdate { player_id, map };
+        for addr in players.values().chain(spectators.values()) {
+            addr.do_send(vote_update.clone());
+        }
+    }
+
+    /// Whether all players have voted for a mode.
+    fn all_voted(&self) -> bool {
         self.votes.len() >= self.required_players
     }
 
-    /// Finalize the mode choice, picking randomly if needed.
+    /// Finalize the mode choice, picking the mode and the map randomly among the votes if
+    /// needed. The map is announced once loaded (see [`ModeChoice::broadcast_chosen_map`]).
     pub fn finalize_mode_choice(
         &mut self,
         player_infos: &[PlayerInfo],
@@ -86,25 +139,55 @@ impl ModeChoice {
         spectators: &std::collections::HashMap<WalletAddress, Addr<GameSessionActor>>,
     ) {
         let (chosen_mode, chosen_by) = if !self.votes.is_empty() {
-            let mut rng = rand::rng();
-            let (chosen_player, mode) = self.votes.iter().choose(&mut rng).unwrap();
-            (mode.clone(), chosen_player.clone())
+            // Sort the votes so the pick does not depend on HashMap iteration order.
+            let mut votes: Vec<(&WalletAddress, &GameMode)> = self.votes.iter().collect();
+            votes.sort_by(|a, b| a.0.cmp(b.0));
+            let (chosen_player, mode) = votes.into_iter().choose(&mut self.rng).unwrap();
+            (*mode, chosen_player.clone())
         } else {
-            let modes = [GameMode::Classic, GameMode::Cracked];
-            let mut rng = rand::rng();
-            let mode = *modes.iter().choose(&mut rng).unwrap();
-            let chosen_player = player_infos.iter().choose(&mut rng).unwrap().id.clone();
+            let mode = available_modes().into_iter().choose(&mut self.rng).unwrap();
+            let chosen_player = player_infos.iter().choose(&mut self.rng).unwrap().id.clone();
             (mode, chosen_player)
         };
-        self.chosen_mode = Some(chosen_mode.clone());
+        self.chosen_mode = Some(chosen_mode);
         self.chosen_by = Some(chosen_by.clone());
         for addr in players.values().chain(spectators.values()) {
             addr.do_send(GameModeChosen {
-                mode: chosen_mode.clone(),
+                mode: chosen_mode,
                 chosen_by: chosen_by.clone(),
             });
         }
         info!("[ModeChoice] Mode chosen: {:?} by {}", chosen_mode, chosen_by);
+
+        // Without map votes, the game is played on the default open grid.
+        let mut map_votes: Vec<(&WalletAddress, &String)> = self.map_votes.iter().collect();
+        map_votes.sort_by(|a, b| a.0.cmp(b.0));
+        let (map, map_chosen_by) = match map_votes.into_iter().choose(&mut self.rng) {
+            Some((player, map)) => (Some(map.clone()), Some(player.clone())),
+            None => (None, None),
+        };
+        info!("[ModeChoice] Map chosen: {:?} by {:?}", map, map_chosen_by);
+        self.chosen_map = map;
+        self.chosen_map_by = map_chosen_by;
+    }
+
+    /// Broadcast the map of the game (`None` for the open grid, also used when the chosen
+    /// map could not be loaded) with its size, which becomes the grid size of the game.
+    pub fn broadcast_chosen_map(
+        &mut self,
+        map: Option<String>,
+        grid_row: usize,
+        grid_col: usize,
+        players: &std::collections::HashMap<WalletAddress, Addr<GameSessionActor>>,
+        spectators: &std::collections::HashMap<WalletAddress, Addr<GameSessionActor>>,
+    ) {
+        self.grid_row = grid_row;
+        self.grid_col = grid_col;
+        let chosen_by = self.chosen_map_by.clone().filter(|_| map.is_some());
+        let msg = GameMapChosen { map, chosen_by, grid_row, grid_col };
+        for addr in players.values().chain(spectators.values()) {
+            addr.do_send(msg.clone());
+        }
     }
 
     /// Reset the mode choice phase (for restarts).
@@ -112,7 +195,10 @@ impl ModeChoice {
         self.votes.clear();
         self.chosen_mode = None;
         self.chosen_by = None;
-        self.deadline = Instant::now() + Duration::from_secs(MODE_CHOICE_DURATION);
+        self.map_votes.clear();
+        self.chosen_map = None;
+        self.chosen_map_by = None;
+        self.deadline = Instant::now() + self.duration;
         self.timer = None;
     }
 }
\ No newline at end of file
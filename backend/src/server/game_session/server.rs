@@ -4,27 +4,35 @@
 //! player registration, game state progression, mode voting, and turn resolution.
 
 use actix::prelude::*;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 use log::{info, warn, debug};
 use serde_json::json;
 
 use crate::game::state::GameState;
+use crate::game::events::GameEvent;
+use crate::game::view::Vision;
+use crate::game::grid::{map_by_name, map_names, GameMap};
+use crate::game::agents::bot_action;
+use crate::game::modes::mode_rules;
+use crate::game::replay::Replay;
 use crate::server::matchmaking::types::{PlayerInfo, WalletAddress};
 use crate::server::game_session::session::GameSessionActor;
-use crate::config::game::{TURN_DURATION, GRID_ROW, GRID_COL};
+use crate::config::game::GameRules;
 use crate::server::session_utils::{is_game_session_addr_valid, is_game_session_spectator_addr_valid};
-use crate::game::types::GameMode;
+use crate::game::types::{GameMode, Position, SpawnStrategy};
 use crate::server::game_session::messages::{
     GameStateUpdate, ProcessClientMessage, PlayerAction, RegisterPendingGame, EnsureGameSession,
-    GameModeVote, SessionKicked, SendWsTextMessage
+    GameModeVote, GameMapVote, GameSpawnDraft, GameSpawnPick, GameSpawnPickUpdate, SessionKicked, SendWsTextMessage,
+    GameInit, GameEnded, ProcessBotMessage,
 };
 use crate::server::ws_error::ws_error_message;
 use crate::server::game_session::mode_choice::ModeChoice;
 use crate::server::game_session::turn_resolution::{start_new_turn, resolve_turn};
 
 /// Stores pending games waiting for session creation.
+#[derive(Default)]
 pub struct PendingGames {
     pub pending: HashMap<Uuid, Vec<PlayerInfo>>,
 }
@@ -45,9 +53,10 @@ impl PendingGames {
 }
 
 /// Manages all game sessions and pending games.
+#[derive(Default)]
 pub struct GameSessionManager {
     sessions: HashMap<Uuid, Addr<GameSession>>,
-    pending_games: HashMap<Uuid, Vec<PlayerInfo>>,
+    pending_games: HashMap<Uuid, (Vec<PlayerInfo>, GameRules)>,
 }
 
 impl GameSessionManager {
@@ -60,8 +69,8 @@ impl GameSessionManager {
     }
 
     /// Register a pending game (called by matchmaking).
-    pub fn register_pending_game(&mut self, game_id: Uuid, players: Vec<PlayerInfo>) {
-        self.pending_games.insert(game_id, players);
+    pub fn register_pending_game(&mut self, game_id: Uuid, players: Vec<PlayerInfo>, rules: GameRules) {
+        self.pending_games.insert(game_id, (players, rules));
     }
 
     /// Ensure a GameSession exists for the given game_id, creating it if needed.
@@ -71,9 +80,9 @@ impl GameSessionManager {
             return Ok(addr.clone());
         }
         // If not, check for pending players and create a new session.
-        let players = self.pending_games.remove(&game_id)
-            .ok_or_else(|| "No player group found for this game_id".to_string())?;
-        let session = GameSession::new(game_id, players).start();
+        let (players, rules) = self.pending_games.remove(&game_id)
+            .ok_or("No player group found for this game_id".to_string())?;
+        let session = GameSession::new(game_id, players, rules).start();
         self.sessions.insert(game_id, session.clone());
         Ok(session)
     }
@@ -87,7 +96,7 @@ impl Handler<RegisterPendingGame> for GameSessionManager {
     type Result = ();
 
     fn handle(&mut self, msg: RegisterPendingGame, _: &mut Context<Self>) -> Self::Result {
-        self.register_pending_game(msg.game_id, msg.players);
+        self.register_pending_game(msg.game_id, msg.players, msg.rules);
     }
 }
 
@@ -106,10 +115,27 @@ pub struct GameSession {
     pub players: HashMap<WalletAddress, Addr<GameSessionActor>>,
     pub spectators: HashMap<WalletAddress, Addr<GameSessionActor>>,
     pub game_state: Option<GameState>,
+    /// Events of the last resolved turn, sent along with the state.
+    pub last_events: Vec<GameEvent>,
+    /// Seed of this game, drawn once at session creation and used for every random decision.
+    pub seed: u64,
+    /// Rule set of this game.
+    pub rules: GameRules,
+    /// Record of the game so far, written to a replay file when it ends.
+    pub replay: Option<Replay>,
 
     // Mode choice phase
     pub mode_choice: ModeChoice,
 
+    // Spawn draft phase
+    /// Map of the game, fixed at the end of the mode choice.
+    pub map: Option<GameMap>,
+    /// Name of the chosen map, if it could be loaded.
+    pub map_name: Option<String>,
+    /// Tiles players may pick, while the spawn draft is open.
+    pub draft_candidates: Option<Vec<Position>>,
+    pub draft_timer: Option<SpawnHandle>,
+
     // In-game phase
     pub pending_actions: HashMap<WalletAddress, PlayerAction>,
     pub turn_timer: Option<SpawnHandle>,
@@ -117,6 +143,7 @@ pub struct GameSession {
     pub turn_start_time: Option<Instant>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone, PartialEq)]
 enum GamePhase {
     WaitingForModeChoice,
@@ -124,16 +151,26 @@ enum GamePhase {
 }
 
 impl GameSession {
-    /// Create a new game session for the given players.
-    pub fn new(game_id: Uuid, player_infos: Vec<PlayerInfo>) -> Self {
-        let required_players = player_infos.len();
+    /// Create a new game session for the given players and rules.
+    pub fn new(game_id: Uuid, player_infos: Vec<PlayerInfo>, rules: GameRules) -> Self {
+        // Bots do not vote.
+        let required_players = player_infos.iter().filter(|p| p.bot.is_none()).count();
+        let seed = rand::random();
         Self {
             game_id,
             player_infos,
             players: HashMap::new(),
             spectators: HashMap::new(),
             game_state: None,
-            mode_choice: ModeChoice::new(required_players),
+            last_events: Vec::new(),
+            seed,
+            mode_choice: ModeChoice::new(required_players, seed, &rules),
+            map: None,
+            map_name: None,
+            draft_candidates: None,
+            draft_timer: None,
+            rules,
+            replay: None,
             pending_actions: HashMap::new(),
             turn_timer: None,
             turn_in_progress: false,
@@ -159,22 +196,108 @@ impl GameSession {
         info!("[GameSession] Mode choice started for game_id={}", self.game_id);
     }
 
-    /// Finalize the mode choice, either by votes or randomly if no votes.
+    /// Finalize the mode choice, either by votes or randomly if no votes, and fix the map.
+    /// The game starts right away, unless the mode spawns players with a draft.
     fn finalize_mode_choice(&mut self, ctx: &mut Context<Self>) {
         self.mode_choice.finalize_mode_choice(
             &self.player_infos,
             &self.players,
             &self.spectators,
         );
-        // Initialize the game state with the chosen mode.
-        let chosen_mode = self.mode_choice.chosen_mode.clone().expect("Mode should be chosen before broadcasting");
-        self.game_state = Some(GameState::new(
-            GRID_ROW, GRID_COL, self.player_infos.clone(), chosen_mode,
-        ));
         // Cancel the mode choice timer if it was set.
         if let Some(handle) = self.mode_choice.timer.take() {
             ctx.cancel_future(handle);
         }
+        let chosen_mode = self.mode_choice.chosen_mode.expect("Mode should be chosen before broadcasting");
+        let (rows, cols) = (self.rules.grid_rows, self.rules.grid_cols);
+        let map = self.mode_choice.chosen_map.as_deref().and_then(|name| map_by_name(name, rows, cols, self.rules.topology, self.seed));
+        // Only a map that could be loaded is recorded, like the state built from it.
+        self.map_name = map.as_ref().and(self.mode_choice.chosen_map.clone());
+        let map = map.unwrap_or_else(|| GameMap::open(rows, cols));
+        // The map decides the grid size, whatever the rules say.
+        self.rules.grid_rows = map.grid.len();
+        self.rules.grid_cols = map.grid.first().map_or(0, |row| row.len());
+        self.mode_choice.broadcast_chosen_map(
+            self.map_name.clone(),
+            self.rules.grid_rows,
+            self.rules.grid_cols,
+            &self.players,
+            &self.spectators,
+        );
+        self.map = Some(map);
+        if mode_rules(chosen_mode).spawn_strategy(&self.rules) == SpawnStrategy::Draft {
+            self.open_spawn_draft(ctx);
+        } else {
+            self.start_game(ctx);
+        }
+    }
+
+    /// Open the spawn draft on the chosen map. The game starts once every player has picked
+    /// a tile, or when the draft deadline passes.
+    fn open_spawn_draft(&mut self, ctx: &mut Context<Self>) {
+        let chosen_mode = self.mode_choice.chosen_mode.expect("Mode should be chosen before the spawn draft");
+        let map = self.map.as_ref().expect("Map should be chosen before the spawn draft");
+        let candidates = GameState::draft_candidates(chosen_mode, map);
+        for info in &mut self.player_infos {
+            info.spawn_pick = None;
+        }
+        let deadline_secs = self.rules.spawn_draft_duration_secs;
+        let draft = GameSpawnDraft { candidates: candidates.clone(), deadline_secs };
+        for addr in self.players.values().chain(self.spectators.values()) {
+            addr.do_send(draft.clone());
+        }
+        self.draft_candidates = Some(candidates);
+        let handle = ctx.run_later(Duration::from_secs(deadline_secs), |act, ctx| {
+            act.start_game(ctx);
+        });
+        self.draft_timer = Some(handle);
+        info!("[GameSession] Spawn draft opened for game_id={}", self.game_id);
+    }
+
+    /// Why a spawn pick is rejected, as an error code and message, or `None` if it can be
+    /// recorded.
+    pub fn spawn_pick_error(&self, player_id: &WalletAddress, pos: Position) -> Option<(&'static str, &'static str)> {
+        let Some(candidates) = self.draft_candidates.as_ref().filter(|_| self.game_state.is_none()) else {
+            return Some(("SPAWN_PICK_CLOSED", "Spawn tiles can only be picked during the spawn draft."));
+        };
+        if !self.player_infos.iter().any(|p| &p.id == player_id) {
+            Some(("UNKNOWN_PLAYER", "Unknown player: you are not part of this game."))
+        } else if !candidates.contains(&pos) {
+            Some(("SPAWN_INVALID", "This tile is not a spawn tile of the chosen map."))
+        } else if self.player_infos.iter().any(|p| &p.id != player_id && p.spawn_pick == Some(pos)) {
+            Some(("SPAWN_TAKEN", "Another player already picked this spawn tile."))
+        } else {
+            None
+        }
+    }
+
+    /// Start the game on the chosen map, closing the spawn draft if it was open.
+    fn start_game(&mut self, ctx: &mut Context<Self>) {
+        if let Some(handle) = self.draft_timer.take() {
+            ctx.cancel_future(handle);
+        }
+        self.draft_candidates = None;
+        // Initialize the game state with the chosen mode, on the chosen map.
+        let chosen_mode = self.mode_choice.chosen_mode.expect("Mode should be chosen before broadcasting");
+        let map = self.map.as_ref().expect("Map should be chosen before the game starts");
+        let state = GameState::new_on_map(
+            self.rules.clone(), self.player_infos.clone(), chosen_mode, map, self.seed,
+        );
+        info!(
+            "[GameSession] Game started: game_id={} mode={:?} map={:?} seed={}",
+            self.game_id, chosen_mode, self.map_name, self.seed
+        );
+        // With the seed, clients could replay every random draw: hold it back until the end
+        // of the game when the mode hides information.
+        let hidden = mode_rules(chosen_mode).vision_radius(&self.rules).is_some();
+        let seed = (!hidden).then_some(self.seed);
+        for (viewer, addr) in self.recipients() {
+            let view = Vision::of(&state, viewer).view(&state);
+            addr.do_send(GameInit { state: view, mode: chosen_mode, seed });
+        }
+        self.replay = Some(Replay::new(&state, self.player_infos.clone(), self.map_name.clone()));
+        self.game_state = Some(state);
+        self.last_events.clear();
         self.turn_in_progress = false;
         self.turn_start_time = None;
         start_new_turn(self, ctx);
@@ -193,6 +316,11 @@ impl GameSession {
         }
     }
 
+    /// Register a map vote from a player.
+    fn receive_map_vote(&mut self, player_id: WalletAddress, map: String) {
+        self.mode_choice.receive_map_vote(player_id, map, &self.players, &self.spectators);
+    }
+
     /// Broadcast the current game state to all players and spectators.
     pub fn send_state(&self) {
         if let Some(ref state) = self.game_state {
@@ -204,27 +332,53 @@ impl GameSession {
                 state.players.iter().map(|p| &p.id).collect::<Vec<_>>(),
                 turn_duration
             );
-            for addr in self.players.values().chain(self.spectators.values()) {
-                addr.do_send(GameStateUpdate { state: state.clone(), turn_duration });
+            let shot_targets = state.shot_targets();
+            for (viewer, addr) in self.recipients() {
+                addr.do_send(self.state_update_for(state, viewer, turn_duration, &shot_targets));
             }
         }
     }
 
+    /// Every player and spectator session, with the player ID of the recipient (`None`
+    /// for spectators).
+    pub fn recipients(&self) -> impl Iterator<Item = (Option<u8>, &Addr<GameSessionActor>)> {
+        let players = self.players.iter().map(|(wallet, addr)| (self.player_id_of(wallet), addr));
+        players.chain(self.spectators.values().map(|addr| (None, addr)))
+    }
+
+    /// Player ID of a wallet in this game.
+    pub fn player_id_of(&self, wallet: &WalletAddress) -> Option<u8> {
+        self.player_infos.iter().position(|p| &p.id == wallet).map(|i| (i + 1) as u8)
+    }
+
+    /// State update as seen by `viewer` (see [`Vision`]), with the last turn's events.
+    pub fn state_update_for(
+        &self,
+        state: &GameState,
+        viewer: Option<u8>,
+        turn_duration: u64,
+        shot_targets: &BTreeMap<u8, Vec<Position>>,
+    ) -> GameStateUpdate {
+        let vision = Vision::of(state, viewer);
+        GameStateUpdate {
+            state: vision.view(state),
+            turn_duration,
+            events: vision.events(state, &self.last_events),
+            shot_targets: vision.shot_targets(shot_targets),
+        }
+    }
+
     /// Calculate the actual remaining time for the current turn (in seconds).
     pub fn get_turn_remaining_secs(&self) -> u64 {
         if self.turn_in_progress {
             if let Some(start) = self.turn_start_time {
                 let elapsed = Instant::now().saturating_duration_since(start).as_secs();
-                if elapsed >= TURN_DURATION {
-                    0
-                } else {
-                    TURN_DURATION - elapsed
-                }
+                self.rules.turn_duration_secs.saturating_sub(elapsed)
             } else {
-                TURN_DURATION
+                self.rules.turn_duration_secs
             }
         } else {
-            TURN_DURATION
+            self.rules.turn_duration_secs
         }
     }
 }
@@ -256,6 +410,52 @@ impl Handler<GameModeVote> for GameSession {
     }
 }
 
+// Handler for GameMapVote: rejects unknown maps, then records the vote.
+impl Handler<GameMapVote> for GameSession {
+    type Result = ();
+
+    fn handle(&mut self, msg: GameMapVote, _ctx: &mut Context<Self>) -> Self::Result {
+        if !map_names().contains(&msg.map) {
+            msg.addr.do_send(SendWsTextMessage {
+                text: ws_error_message(
+                    "UNKNOWN_MAP",
+                    "Unknown map: vote for one of the maps listed in the pre-game data.",
+                    Some(json!({ "wallet": msg.player_id, "map": msg.map })),
+                ),
+            });
+            return;
+        }
+        self.receive_map_vote(msg.player_id, msg.map);
+    }
+}
+
+// Handler for GameSpawnPick: records the pick of a player in the spawn draft.
+// Picks are only accepted while the draft is open, on a spawn tile of the chosen map that no
+// other player picked. The game starts as soon as every player has picked.
+impl Handler<GameSpawnPick> for GameSession {
+    type Result = ();
+
+    fn handle(&mut self, msg: GameSpawnPick, ctx: &mut Context<Self>) -> Self::Result {
+        if let Some((code, message)) = self.spawn_pick_error(&msg.player_id, msg.pos) {
+            let context = Some(json!({ "wallet": msg.player_id, "pos": msg.pos }));
+            msg.addr.do_send(SendWsTextMessage { text: ws_error_message(code, message, context) });
+            return;
+        }
+
+        if let Some(info) = self.player_infos.iter_mut().find(|p| p.id == msg.player_id) {
+            info.spawn_pick = Some(msg.pos);
+        }
+        let update = GameSpawnPickUpdate { player_id: msg.player_id, pos: msg.pos };
+        for addr in self.players.values().chain(self.spectators.values()) {
+            addr.do_send(update.clone());
+        }
+        // Bots do not pick: they spawn as far as possible from the others.
+        if self.player_infos.iter().filter(|p| p.bot.is_none()).all(|p| p.spawn_pick.is_some()) {
+            self.start_game(ctx);
+        }
+    }
+}
+
 impl Handler<GetGameSession> for GameSessionManager {
     type Result = Result<Addr<GameSession>, String>;
 
@@ -301,6 +501,31 @@ impl Handler<IsPlayer> for GameSession {
     }
 }
 
+/// Current turn of a session and the wallets of the players who already acted in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TurnStatus {
+    /// Turn in progress (0 before the game starts).
+    pub turn: u32,
+    /// Players whose action for this turn is registered.
+    pub acted: Vec<WalletAddress>,
+}
+
+/// Message to get the [`TurnStatus`] of a session.
+#[derive(Message)]
+#[rtype(result = "TurnStatus")]
+pub struct GetTurnStatus;
+
+impl Handler<GetTurnStatus> for GameSession {
+    type Result = MessageResult<GetTurnStatus>;
+
+    fn handle(&mut self, _: GetTurnStatus, _: &mut Context<Self>) -> Self::Result {
+        MessageResult(TurnStatus {
+            turn: self.game_state.as_ref().map_or(0, |state| state.turn),
+            acted: self.pending_actions.keys().cloned().collect(),
+        })
+    }
+}
+
 impl Handler<ProcessClientMessage> for GameSession {
     type Result = ();
 
@@ -318,73 +543,95 @@ impl Handler<ProcessClientMessage> for GameSession {
             return;
         }
 
-        // Ignore actions if the game hasn't started.
-        if self.game_state.is_none() {
+        // Bot seats are played by the session, never by a client.
+        if self.player_infos.iter().any(|p| p.id == msg.player_id && p.bot.is_some()) {
             msg.addr.do_send(SendWsTextMessage {
                 text: ws_error_message(
-                    "GAME_NOT_STARTED",
-                    "Game has not started yet. You cannot act now.",
+                    "UNKNOWN_PLAYER",
+                    "Unknown player: you are not part of this game.",
                     Some(json!(msg.player_id)),
                 ),
             });
             return;
         }
+
+        if let Err(text) = self.submit_action(msg.player_id, msg.msg, ctx) {
+            msg.addr.do_send(SendWsTextMessage { text });
+        }
+    }
+}
+
+// Handler for ProcessBotMessage: bot actions go through the same checks as client actions.
+impl Handler<ProcessBotMessage> for GameSession {
+    type Result = ();
+
+    fn handle(&mut self, msg: ProcessBotMessage, ctx: &mut Context<Self>) -> Self::Result {
+        let wallet = msg.player_id.clone();
+        if let Err(text) = self.submit_action(msg.player_id, msg.msg, ctx) {
+            warn!("[GameSession] Bot action rejected for wallet={}: {}", wallet, text);
+        }
+    }
+}
+
+impl GameSession {
+    /// Register the action of a seated player (human or bot) for the current turn.
+    ///
+    /// Once every living player has acted, the turn is resolved immediately. Returns the
+    /// WebSocket error to send back when the action is rejected.
+    fn submit_action(&mut self, player_id: WalletAddress, action: PlayerAction, ctx: &mut Context<Self>) -> Result<(), String> {
+        // Ignore actions if the game hasn't started.
+        if self.game_state.is_none() {
+            return Err(ws_error_message(
+                "GAME_NOT_STARTED",
+                "Game has not started yet. You cannot act now.",
+                Some(json!(player_id)),
+            ));
+        }
         // Ignore actions if the turn is not in progress.
         if !self.turn_in_progress {
             warn!("[GameSession] Action received while turn is not in progress");
-            msg.addr.do_send(SendWsTextMessage {
-                text: ws_error_message(
-                    "TURN_NOT_IN_PROGRESS",
-                    "Turn is not in progress. Please wait for your turn.",
-                    Some(json!(msg.player_id)),
-                ),
-            });
-            return;
+            return Err(ws_error_message(
+                "TURN_NOT_IN_PROGRESS",
+                "Turn is not in progress. Please wait for your turn.",
+                Some(json!(player_id)),
+            ));
         }
 
-        // Find the player's index.
-        let player_index = match self.player_infos.iter().position(|p| p.id == msg.player_id) {
-            Some(idx) => idx,
-            None => {
-                msg.addr.do_send(SendWsTextMessage {
-                    text: ws_error_message(
-                        "UNKNOWN_PLAYER",
-                        "Unknown player: you are not part of this game.",
-                        Some(json!(msg.player_id)),
-                    ),
-                });
-                return;
-            }
+        // Find the player's ID in the game.
+        let Some(game_player_id) = self.player_id_of(&player_id) else {
+            return Err(ws_error_message(
+                "UNKNOWN_PLAYER",
+                "Unknown player: you are not part of this game.",
+                Some(json!(player_id)),
+            ));
         };
 
-        // Only allow actions from living players.
-        if !self.game_state.as_ref().unwrap().players.get(player_index).map(|p| p.is_alive).unwrap_or(false) {
-            warn!("[GameSession] Dead or unknown player tried to act: {}", msg.player_id);
-            msg.addr.do_send(SendWsTextMessage {
-                text: ws_error_message(
-                    "PLAYER_ELIMINATED",
-                    "You are eliminated and cannot act anymore.",
-                    Some(json!(msg.player_id)),
-                ),
-            });
-            return;
+        // Prevent multiple actions per turn.
+        if self.pending_actions.contains_key(&player_id) {
+            warn!("[GameSession] Player {} spamming, action already received this turn", player_id);
+            return Err(ws_error_message(
+                "ALREADY_ACTED",
+                "You have already acted this turn. Please wait for the next turn.",
+                Some(json!(player_id)),
+            ));
         }
 
-        // Prevent multiple actions per turn.
-        if self.pending_actions.contains_key(&msg.player_id) {
-            warn!("[GameSession] Player {} spamming, action already received this turn", msg.player_id);
-            msg.addr.do_send(SendWsTextMessage {
-                text: ws_error_message(
-                    "ALREADY_ACTED",
-                    "You have already acted this turn. Please wait for the next turn.",
-                    Some(json!(msg.player_id)),
-                ),
-            });
-            return;
+        // Reject actions that are not valid in the current state (eliminated player,
+        // out of bounds, no ammo, illegal target, tile another player is shooting at...).
+        let state = self.game_state.as_ref().unwrap();
+        let others = self.pending_actions.iter()
+            .filter_map(|(wallet, other)| Some((self.player_id_of(wallet)?, other)));
+        if let Err(error) = state.validate_turn_action(game_player_id, &action, others) {
+            warn!("[GameSession] Invalid action from {}: {:?} ({})", player_id, action, error.code());
+            return Err(ws_error_message(
+                error.code(),
+                &error.to_string(),
+                Some(json!({ "wallet": player_id, "action": action })),
+            ));
         }
 
         // Register the action.
-        self.pending_actions.insert(msg.player_id.clone(), msg.msg);
+        self.pending_actions.insert(player_id, action);
 
         // If all living players have acted, resolve the turn immediately.
         let alive_count = self.game_state.as_ref().unwrap().players.iter().filter(|p| p.is_alive).count();
@@ -395,6 +642,31 @@ impl Handler<ProcessClientMessage> for GameSession {
             }
             resolve_turn(self, ctx);
         }
+        Ok(())
+    }
+
+    /// Let every living bot choose its action for the current turn.
+    ///
+    /// Actions are sent to the session as [`ProcessBotMessage`]s, so they are registered
+    /// after the turn has started, like client actions.
+    pub fn play_bots(&self, ctx: &mut Context<Self>) {
+        let Some(state) = self.game_state.as_ref() else {
+            return;
+        };
+        for info in &self.player_infos {
+            let Some(kind) = info.bot else {
+                continue;
+            };
+            // Players who failed to spawn are missing from the state: look bots up by ID.
+            let Some(id) = self.player_id_of(&info.id) else {
+                continue;
+            };
+            if !state.players.iter().any(|p| p.id == id && p.is_alive) {
+                continue;
+            }
+            let action = bot_action(kind, state, id);
+            ctx.address().do_send(ProcessBotMessage { msg: action, player_id: info.id.clone() });
+        }
     }
 }
 
@@ -454,11 +726,14 @@ impl Handler<RegisterSession> for GameSession {
                 &self.spectators,
                 &self.player_infos,
             );
-        } else {
-            if let Some(ref state) = self.game_state {
-                // Use the real remaining time, not TURN_DURATION
-                let turn_duration = self.get_turn_remaining_secs();
-                msg.addr.do_send(GameStateUpdate { state: state.clone(), turn_duration });
+        } else if let Some(ref state) = self.game_state {
+            // Use the real remaining time, not the full turn duration
+            let turn_duration = self.get_turn_remaining_secs();
+            let viewer = if msg.is_player { self.player_id_of(&msg.wallet) } else { None };
+            msg.addr.do_send(self.state_update_for(state, viewer, turn_duration, &state.shot_targets()));
+            // A late session on a finished game still gets the final result.
+            if let Some(outcome) = state.outcome() {
+                msg.addr.do_send(GameEnded { outcome, seed: self.seed });
             }
         }
     }
@@ -16,8 +16,10 @@ use crate::server::game_session::server::{GameSession, UnregisterSession, Regist
 use crate::server::game_session::messages::{
     GamePreGameData, GameModeChosen, ProcessClientMessage, GameStateUpdate, PlayerAction,
     GameWsMessage, EnsureGameSession, GameModeVoteUpdate, GameClientWsMessage, GameModeVote,
-    SessionKicked, SendWsTextMessage
+    SessionKicked, SendWsTextMessage, GameInit, GameEnded, GameMapVote, GameMapVoteUpdate,
+    GameMapChosen, GameSpawnDraft, GameSpawnPick, GameSpawnPickUpdate,
 };
+use crate::game::types::Position;
 use crate::server::matchmaking::types::WalletAddress;
 use crate::server::ws_error::{http_error_response, ws_session_kicked_message};
 use crate::server::anti_spam::AntiSpamState;
@@ -147,6 +149,14 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for GameSessionActor
                         });
                         self.anti_spam.reset_on_valid_action();
                     }
+                    GameClientWsMessage::UseItem(item) => {
+                        self.session_addr.do_send(ProcessClientMessage {
+                            msg: PlayerAction::UseItem(item),
+                            player_id: self.player_id.clone(),
+                            addr: ctx.address(),
+                        });
+                        self.anti_spam.reset_on_valid_action();
+                    }
                     GameClientWsMessage::GameModeVote { mode } => {
                         // Forward the mode vote to the session.
                         self.session_addr.do_send(GameModeVote {
@@ -155,6 +165,24 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for GameSessionActor
                         });
                         self.anti_spam.reset_on_valid_action();
                     }
+                    GameClientWsMessage::GameMapVote { map } => {
+                        // Forward the map vote to the session.
+                        self.session_addr.do_send(GameMapVote {
+                            player_id: self.player_id.clone(),
+                            map,
+                            addr: ctx.address(),
+                        });
+                        self.anti_spam.reset_on_valid_action();
+                    }
+                    GameClientWsMessage::GameSpawnPick { x, y } => {
+                        // Forward the spawn pick to the session.
+                        self.session_addr.do_send(GameSpawnPick {
+                            player_id: self.player_id.clone(),
+                            pos: Position { x, y },
+                            addr: ctx.address(),
+                        });
+                        self.anti_spam.reset_on_valid_action();
+                    }
                     // Add other variants here if needed.
                 }
             }
@@ -233,6 +261,114 @@ impl Handler<GameModeVoteUpdate> for GameSessionActor {
     }
 }
 
+impl Handler<GameMapVoteUpdate> for GameSessionActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: GameMapVoteUpdate, ctx: &mut Self::Context) -> Self::Result {
+        let ws_msg = GameWsMessage::GameMapVoteUpdate(msg);
+        match serde_json::to_string(&ws_msg) {
+            Ok(text) => {
+                self.send_json_or_ban(ctx, text);
+            },
+            Err(e) => self.send_explicit_error(
+                ctx,
+                "SERIALIZATION_ERROR",
+                &format!("Failed to serialize map vote update: {}", e),
+            ),
+        }
+    }
+}
+
+impl Handler<GameMapChosen> for GameSessionActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: GameMapChosen, ctx: &mut Self::Context) -> Self::Result {
+        let ws_msg = GameWsMessage::GameMapChosen(msg);
+        match serde_json::to_string(&ws_msg) {
+            Ok(text) => {
+                self.send_json_or_ban(ctx, text);
+            },
+            Err(e) => self.send_explicit_error(
+                ctx,
+                "SERIALIZATION_ERROR",
+                &format!("Failed to serialize chosen map: {}", e),
+            ),
+        }
+    }
+}
+
+impl Handler<GameSpawnDraft> for GameSessionActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: GameSpawnDraft, ctx: &mut Self::Context) -> Self::Result {
+        let ws_msg = GameWsMessage::GameSpawnDraft(msg);
+        match serde_json::to_string(&ws_msg) {
+            Ok(text) => {
+                self.send_json_or_ban(ctx, text);
+            },
+            Err(e) => self.send_explicit_error(
+                ctx,
+                "SERIALIZATION_ERROR",
+                &format!("Failed to serialize spawn draft: {}", e),
+            ),
+        }
+    }
+}
+
+impl Handler<GameSpawnPickUpdate> for GameSessionActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: GameSpawnPickUpdate, ctx: &mut Self::Context) -> Self::Result {
+        let ws_msg = GameWsMessage::GameSpawnPickUpdate(msg);
+        match serde_json::to_string(&ws_msg) {
+            Ok(text) => {
+                self.send_json_or_ban(ctx, text);
+            },
+            Err(e) => self.send_explicit_error(
+                ctx,
+                "SERIALIZATION_ERROR",
+                &format!("Failed to serialize spawn pick update: {}", e),
+            ),
+        }
+    }
+}
+
+impl Handler<GameInit> for GameSessionActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: GameInit, ctx: &mut Self::Context) -> Self::Result {
+        let ws_msg = GameWsMessage::GameInit { state: msg.state, mode: msg.mode, seed: msg.seed };
+        match serde_json::to_string(&ws_msg) {
+            Ok(text) => {
+                self.send_json_or_ban(ctx, text);
+            },
+            Err(e) => self.send_explicit_error(
+                ctx,
+                "SERIALIZATION_ERROR",
+                &format!("Failed to serialize initial game state: {}", e),
+            ),
+        }
+    }
+}
+
+impl Handler<GameEnded> for GameSessionActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: GameEnded, ctx: &mut Self::Context) -> Self::Result {
+        let ws_msg = GameWsMessage::GameEnded { outcome: msg.outcome, seed: msg.seed };
+        match serde_json::to_string(&ws_msg) {
+            Ok(text) => {
+                self.send_json_or_ban(ctx, text);
+            },
+            Err(e) => self.send_explicit_error(
+                ctx,
+                "SERIALIZATION_ERROR",
+                &format!("Failed to serialize game outcome: {}", e),
+            ),
+        }
+    }
+}
+
 impl Handler<GameStateUpdate> for GameSessionActor {
     type Result = ();
 
@@ -242,10 +378,15 @@ impl Handler<GameStateUpdate> for GameSessionActor {
             self.player_id,
             self.is_player,
             msg.state.turn,
-            msg.state.players.iter().map(|p| (p.id.clone(), p.pos, p.is_alive)).collect::<Vec<_>>(),
+            msg.state.players.iter().map(|p| (p.id, p.pos, p.is_alive)).collect::<Vec<_>>(),
             msg.turn_duration
         );
-        let ws_msg = GameWsMessage::GameStateUpdate { state: msg.state, turn_duration: msg.turn_duration };
+        let ws_msg = GameWsMessage::GameStateUpdate {
+            state: msg.state,
+            turn_duration: msg.turn_duration,
+            events: msg.events,
+            shot_targets: msg.shot_targets,
+        };
         match serde_json::to_string(&ws_msg) {
             Ok(text) => {
                 // Reset error suppression at each turn (new state)
@@ -311,11 +452,8 @@ pub async fn ws_game(
     let mut player_id: Option<WalletAddress> = None;
     for kv in req.query_string().split('&') {
         let mut split = kv.split('=');
-        match (split.next(), split.next()) {
-            (Some("wallet"), Some(addr)) => {
-                player_id = Some(addr.to_string());
-            }
-            _ => {}
+        if let (Some("wallet"), Some(addr)) = (split.next(), split.next()) {
+            player_id = Some(addr.to_string());
         }
     }
     let player_id = match player_id {
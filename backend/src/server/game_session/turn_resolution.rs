@@ -1,12 +1,17 @@
-/// Handles turn start and resolution logic for GameSession.
-/// Encapsulates timer management, action collection, and state updates.
+//! Handles turn start and resolution logic for GameSession.
+//! Encapsulates timer management, action collection, and state updates.
 
+use std::path::Path;
 use std::time::{Duration, Instant};
 use actix::prelude::*;
+use log::{debug, info, warn};
 
 use crate::server::game_session::server::GameSession;
-use crate::server::game_session::messages::GameStateUpdate;
-use crate::config::game::TURN_DURATION;
+use crate::server::game_session::messages::{GameEnded, PlayerAction};
+use crate::game::types::Direction;
+use crate::game::systems::render_grid;
+use crate::game::replay::replay_path;
+use crate::config::game::REPLAY_DIR;
 
 /// Start a new turn: reset actions, launch timer, broadcast state.
 pub fn start_new_turn(this: &mut GameSession, ctx: &mut Context<GameSession>) {
@@ -18,18 +23,14 @@ pub fn start_new_turn(this: &mut GameSession, ctx: &mut Context<GameSession>) {
     this.turn_start_time = Some(Instant::now());
 
     // Start the turn timer.
-    let handle = ctx.run_later(Duration::from_secs(TURN_DURATION), |act, ctx| {
+    let handle = ctx.run_later(Duration::from_secs(this.rules.turn_duration_secs), |act, ctx| {
         resolve_turn(act, ctx);
     });
     this.turn_timer = Some(handle);
 
-    // Broadcast the new turn state.
-    if let Some(ref state) = this.game_state {
-        let turn_duration = this.get_turn_remaining_secs();
-        for addr in this.players.values().chain(this.spectators.values()) {
-            addr.do_send(GameStateUpdate { state: state.clone(), turn_duration });
-        }
-    }
+    // Broadcast the new turn state, then let the bots act.
+    this.send_state();
+    this.play_bots(ctx);
 }
 
 /// Resolve the current turn: apply actions, update state, check for game end.
@@ -39,38 +40,61 @@ pub fn resolve_turn(this: &mut GameSession, ctx: &mut Context<GameSession>) {
     }
     this.turn_in_progress = false;
 
-    let state = this.game_state.as_mut().unwrap();
+    let state = this.game_state.as_ref().unwrap();
 
     // For each living player, if no action was received, default to Stay.
-    for info in &this.player_infos {
-        if !this.pending_actions.contains_key(&info.id) {
-            if let Some(_idx) = state.players.iter().position(|p| p.username == info.username && p.is_alive) {
-                this.pending_actions.insert(info.id.clone(), crate::server::game_session::messages::PlayerAction::Move(crate::game::types::Direction::Stay));
-            }
-        }
-    }
-
-    // Apply all actions in player order.
+    // Player IDs follow the order of the player infos, even if some failed to spawn.
+    let player_of = |i: usize| state.players.iter().find(|p| p.id as usize == i + 1);
     for (i, info) in this.player_infos.iter().enumerate() {
-        if let Some(player) = state.players.get(i) {
-            if !player.is_alive { continue; }
-        }
-        if let Some(action) = this.pending_actions.get(&info.id) {
-            state.apply_player_action(action.clone(), i);
+        if !this.pending_actions.contains_key(&info.id) && player_of(i).is_some_and(|p| p.is_alive) {
+            this.pending_actions.insert(info.id.clone(), PlayerAction::Move(Direction::Stay));
         }
     }
 
-    // Advance the turn counter.
-    state.next_turn();
+    // Collect all actions in player order.
+    let actions: Vec<(u8, PlayerAction)> = this.player_infos.iter().enumerate()
+        .filter_map(|(i, info)| {
+            let player = player_of(i)?;
+            let action = this.pending_actions.get(&info.id)?;
+            Some((player.id, action.clone()))
+        })
+        .collect();
 
-    // If more than one player is alive, start the next turn.
-    if state.players.iter().filter(|p| p.is_alive).count() > 1 {
-        start_new_turn(this, ctx);
-    } else {
-        // Game is over, notify all clients.
-        for addr in this.players.values().chain(this.spectators.values()) {
-            addr.do_send(GameStateUpdate { state: state.clone(), turn_duration: 0 });
-            // TODO: send a GameEnded message if needed
+    // Resolve the turn and keep its events for the broadcast.
+    let (next_state, events) = state.step(&actions);
+    let outcome = next_state.outcome();
+    if let Some(replay) = this.replay.as_mut() {
+        replay.record_turn(&actions, &next_state);
+    }
+    debug!("[GameSession] Grid after turn {} of game_id={}:\n{}", state.turn, this.game_id, render_grid(&next_state));
+    this.game_state = Some(next_state);
+    this.last_events = events;
+
+    match outcome {
+        // Nobody has won yet, start the next turn.
+        None => start_new_turn(this, ctx),
+        // Game is over, send the final state then the outcome to all clients.
+        Some(outcome) => {
+            info!("[GameSession] Game ended: game_id={} result={:?}", this.game_id, outcome.result);
+            save_replay(this);
+            let state = this.game_state.as_ref().unwrap();
+            let shot_targets = state.shot_targets();
+            for (viewer, addr) in this.recipients() {
+                addr.do_send(this.state_update_for(state, viewer, 0, &shot_targets));
+                addr.do_send(GameEnded { outcome: outcome.clone(), seed: this.seed });
+            }
         }
     }
 }
+
+/// Write the replay of the finished game to the replay directory.
+fn save_replay(this: &GameSession) {
+    let Some(replay) = &this.replay else {
+        return;
+    };
+    let path = replay_path(Path::new(REPLAY_DIR), &this.game_id.to_string());
+    match replay.save(&path) {
+        Ok(()) => info!("[GameSession] Replay saved: game_id={} path={}", this.game_id, path.display()),
+        Err(e) => warn!("[GameSession] Could not save replay of game_id={}: {}", this.game_id, e),
+    }
+}
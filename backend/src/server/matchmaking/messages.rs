@@ -1,10 +1,11 @@
-/// Messages exchanged between client and server in the matchmaking lobby.
+//! Messages exchanged between client and server in the matchmaking lobby.
 
 use actix::prelude::*;
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 
 use super::types::PlayerInfo;
+use crate::game::types::AgentKind;
 
 /// State of the matchmaking lobby, sent to clients.
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -27,6 +28,10 @@ pub enum ClientWsMessage {
     Pay,
     /// Player wants to cancel payment and return to lobby.
     CancelPayment,
+    /// Player wants to start a practice game right away against the given bots.
+    Practice {
+        bots: Vec<AgentKind>,
+    },
     /// Ping (keepalive or latency check).
     Ping,
 }
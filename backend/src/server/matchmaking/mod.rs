@@ -1,4 +1,4 @@
-/// Matchmaking module: handles lobby, player readiness, and game assignment.
+//! Matchmaking module: handles lobby, player readiness, and game assignment.
 
 pub mod server;
 pub mod session;
@@ -1,7 +1,7 @@
-/// Matchmaking server actor.
-///
-/// Manages the matchmaking lobby, player readiness, countdowns, and game creation.
-/// Handles player join/leave, payment, and cancellation, and coordinates with the game session manager.
+//! Matchmaking server actor.
+//!
+//! Manages the matchmaking lobby, player readiness, countdowns, and game creation.
+//! Handles player join/leave, payment, and cancellation, and coordinates with the game session manager.
 
 use actix::prelude::*;
 use std::collections::HashMap;
@@ -9,10 +9,12 @@ use std::time::{Duration, Instant};
 use uuid::Uuid;
 use log::{info, debug};
 
-use super::types::{PlayerInfo, WalletAddress};
+use super::types::{PlayerInfo, WalletAddress, assign_teams, bot_info};
 use super::messages::{ServerWsMessage, MatchmakingState};
 use super::session::MatchmakingSession;
 use crate::config::matchmaking::{MIN_PLAYERS, MAX_PLAYERS, COUNTDOWN_DURATION_SECS};
+use crate::config::game::GameRules;
+use crate::game::types::AgentKind;
 use crate::server::game_session::messages::RegisterPendingGame;
 use crate::server::game_session::server::GameSessionManager;
 use crate::server::session_utils::is_matchmaking_session_addr_valid;
@@ -45,6 +47,8 @@ pub struct MatchmakingServer {
     countdown: Option<CountdownHandle>,
     /// Address of the game session manager for launching games.
     game_session_manager: Addr<GameSessionManager>,
+    /// Rule set of the games launched from this lobby.
+    rules: GameRules,
 }
 
 impl MatchmakingServer {
@@ -55,6 +59,7 @@ impl MatchmakingServer {
             ready_groups: Vec::new(),
             countdown: None,
             game_session_manager,
+            rules: GameRules::default(),
         }
     }
 
@@ -123,35 +128,39 @@ impl MatchmakingServer {
     /// Attempt to launch the next game if a ready group has enough players.
     fn try_launch_next_game(&mut self, ctx: &mut Context<Self>) {
         // Find a group with enough players to start a game.
-        if let Some((group_idx, group)) = self.ready_groups.iter().enumerate().find(|(_, g)| g.len() >= MIN_PLAYERS) {
+        if let Some(group_idx) = self.ready_groups.iter().position(|g| g.len() >= MIN_PLAYERS) {
+            let group = self.ready_groups.remove(group_idx);
             let player_infos: Vec<PlayerInfo> = group.values().map(|p| p.info.clone()).collect();
             let player_addrs: Vec<SessionAddr> = group.values().map(|p| p.addr.clone()).collect();
 
             // Remove the countdown since the game is starting.
             self.cancel_countdown(ctx);
+            self.launch_game(player_infos, &player_addrs);
+            self.send_state();
+        }
+    }
 
-            // Generate a new game ID.
-            let game_id = Uuid::new_v4();
-
-            // Register the pending game with the game session manager.
-            self.game_session_manager.do_send(RegisterPendingGame {
-                game_id,
-                players: player_infos.clone(),
-            });
+    /// Create a game for the given players (humans and bots) and send its ID to the
+    /// sessions of the human players.
+    fn launch_game(&self, mut player_infos: Vec<PlayerInfo>, player_addrs: &[SessionAddr]) {
+        assign_teams(&mut player_infos, self.rules.team_count, &mut rand::rng());
 
-            // Notify each player of the new game.
-            for addr in &player_addrs {
-                addr.do_send(ServerWsMessage::GameStarted { game_id });
-            }
+        // Generate a new game ID.
+        let game_id = Uuid::new_v4();
 
-            info!("[Matchmaking] Game created with {} players, game_id={}", player_addrs.len(), game_id);
+        // Register the pending game with the game session manager.
+        self.game_session_manager.do_send(RegisterPendingGame {
+            game_id,
+            players: player_infos.clone(),
+            rules: self.rules.clone(),
+        });
 
-            // Remove the group from the ready list.
-            if group_idx < self.ready_groups.len() {
-                self.ready_groups.remove(group_idx);
-            }
-            self.send_state();
+        // Notify each player of the new game.
+        for addr in player_addrs {
+            addr.do_send(ServerWsMessage::GameStarted { game_id });
         }
+
+        info!("[Matchmaking] Game created with {} players, game_id={}", player_infos.len(), game_id);
     }
 
     /// Add or update a player in the lobby.
@@ -159,6 +168,9 @@ impl MatchmakingServer {
         let player_info = PlayerInfo {
             id: player_id.clone(),
             username,
+            team_id: None,
+            spawn_pick: None,
+            bot: None,
         };
         self.lobby_players.insert(player_id, ConnectedPlayer {
             info: player_info,
@@ -167,12 +179,11 @@ impl MatchmakingServer {
     }
 
     /// Remove a player from all ready groups, but only if the session address matches.
+    #[allow(dead_code)]
     fn remove_player_from_ready_groups(&mut self, player_id: &WalletAddress, addr: &SessionAddr) -> Option<ConnectedPlayer> {
         for group in &mut self.ready_groups {
-            if let Some(_player) = group.get(player_id) {
-                if is_matchmaking_session_addr_valid(group, player_id, addr) {
-                    return group.remove(player_id);
-                }
+            if group.contains_key(player_id) && is_matchmaking_session_addr_valid(group, player_id, addr) {
+                return group.remove(player_id);
             }
         }
         None
@@ -223,6 +234,15 @@ pub struct CancelPayment {
     pub addr: SessionAddr,
 }
 
+/// Message: player starts a practice game against bots, without paying.
+#[derive(Message)]
+#[rtype(result = "()")]
+pub struct Practice {
+    pub player_id: WalletAddress,
+    pub addr: SessionAddr,
+    pub bots: Vec<AgentKind>,
+}
+
 impl Actor for MatchmakingServer {
     type Context = Context<Self>;
 }
@@ -281,23 +301,21 @@ impl Handler<Leave> for MatchmakingServer {
     /// Handles a player leaving the lobby or ready group.
     fn handle(&mut self, msg: Leave, _ctx: &mut Self::Context) -> Self::Result {
         // Remove from lobby if present and session matches.
-        if let Some(_player) = self.lobby_players.get(&msg.player_id) {
-            if is_matchmaking_session_addr_valid(&self.lobby_players, &msg.player_id, &msg.addr) {
-                self.lobby_players.remove(&msg.player_id);
-                debug!("[Matchmaking] Player {} left lobby_players", msg.player_id);
-                self.send_state();
-                return;
-            }
+        if is_matchmaking_session_addr_valid(&self.lobby_players, &msg.player_id, &msg.addr) {
+            self.lobby_players.remove(&msg.player_id);
+            debug!("[Matchmaking] Player {} left lobby_players", msg.player_id);
+            self.send_state();
+            return;
         }
 
         let countdown_active = self.countdown.is_some();
         // If in a ready group, handle leave logic.
         if let Some(group) = self.find_group_of_player_mut(&msg.player_id) {
-            if let Some(_player) = group.get(&msg.player_id) {
-                if !is_matchmaking_session_addr_valid(group, &msg.player_id, &msg.addr) {
-                    // Not the same session, ignore.
-                    return;
-                }
+            if group.contains_key(&msg.player_id)
+                && !is_matchmaking_session_addr_valid(group, &msg.player_id, &msg.addr)
+            {
+                // Not the same session, ignore.
+                return;
             }
             
             if countdown_active {
@@ -313,7 +331,6 @@ impl Handler<Leave> for MatchmakingServer {
             self.ready_groups.retain(|g| !g.is_empty());
             self.refund_player(&msg.player_id);
             self.send_state();
-            return;
         }
     }
 }
@@ -324,14 +341,12 @@ impl Handler<Pay> for MatchmakingServer {
     /// Handles a player paying to become ready.
     fn handle(&mut self, msg: Pay, ctx: &mut Self::Context) -> Self::Result {
         // If already in a ready group, ignore (cannot pay twice).
-        if let Some(group) = self.find_group_of_player_mut(&msg.player_id) {
-            if let Some(_player) = group.get(&msg.player_id) {
-                if is_matchmaking_session_addr_valid(group, &msg.player_id, &msg.addr) {
-                    debug!("[Matchmaking] Player {} tried to pay but is already ready (same session)", msg.player_id);
-                    // TODO: send error message to client if needed.
-                    return;
-                }
-            }
+        if let Some(group) = self.find_group_of_player_mut(&msg.player_id)
+            && is_matchmaking_session_addr_valid(group, &msg.player_id, &msg.addr)
+        {
+            debug!("[Matchmaking] Player {} tried to pay but is already ready (same session)", msg.player_id);
+            // TODO: send error message to client if needed.
+            return;
         }
         
         // Remove from lobby; only if session matches.
@@ -395,11 +410,11 @@ impl Handler<CancelPayment> for MatchmakingServer {
         let group = group.unwrap();
 
         // Check if the session matches
-        if let Some(_player) = group.get(&msg.player_id) {
-            if !is_matchmaking_session_addr_valid(group, &msg.player_id, &msg.addr) {
-                // Not the same session, ignore.
-                return;
-            }
+        if group.contains_key(&msg.player_id)
+            && !is_matchmaking_session_addr_valid(group, &msg.player_id, &msg.addr)
+        {
+            // Not the same session, ignore.
+            return;
         }
 
         if countdown_active {
@@ -422,3 +437,33 @@ impl Handler<CancelPayment> for MatchmakingServer {
         }
     }
 }
+
+impl Handler<Practice> for MatchmakingServer {
+    type Result = ();
+
+    /// Handles a lobby player starting a practice game: the player leaves the lobby for a
+    /// game where bots fill the other seats.
+    fn handle(&mut self, msg: Practice, _ctx: &mut Self::Context) -> Self::Result {
+        if !is_matchmaking_session_addr_valid(&self.lobby_players, &msg.player_id, &msg.addr) {
+            debug!("[Matchmaking] Player {} tried to practice but is not in lobby_players", msg.player_id);
+            msg.addr.do_send(ServerWsMessage::Error {
+                message: "Only players in the lobby can start a practice game.".to_string(),
+            });
+            return;
+        }
+        if msg.bots.is_empty() || msg.bots.len() >= MAX_PLAYERS {
+            msg.addr.do_send(ServerWsMessage::Error {
+                message: format!("A practice game needs 1 to {} bots.", MAX_PLAYERS - 1),
+            });
+            return;
+        }
+
+        let player = self.lobby_players.remove(&msg.player_id).unwrap();
+        let player_infos: Vec<PlayerInfo> = std::iter::once(player.info)
+            .chain(msg.bots.iter().enumerate().map(|(i, &kind)| bot_info(kind, i + 2)))
+            .collect();
+        debug!("[Matchmaking] Player {} started a practice game against {:?}", msg.player_id, msg.bots);
+        self.launch_game(player_infos, &[player.addr]);
+        self.send_state();
+    }
+}
@@ -1,8 +1,8 @@
 
-/// WebSocket session handler for a player in the matchmaking lobby.
-///
-/// Handles incoming client messages (pay, cancel, ping, etc.) and relays server updates.
-/// Centralizes error handling and ensures all business logic is executed.
+//! WebSocket session handler for a player in the matchmaking lobby.
+//!
+//! Handles incoming client messages (pay, cancel, ping, etc.) and relays server updates.
+//! Centralizes error handling and ensures all business logic is executed.
 
 use actix::{Addr, Actor, StreamHandler, Handler, ActorContext, AsyncContext};
 use actix_web::{HttpRequest, HttpResponse, web, Error};
@@ -10,7 +10,7 @@ use actix_web_actors::ws;
 use serde_json::json;
 use log::{info, warn, error, debug};
 
-use crate::server::matchmaking::server::{MatchmakingServer, Join, Leave, Pay, CancelPayment};
+use crate::server::matchmaking::server::{MatchmakingServer, Join, Leave, Pay, CancelPayment, Practice};
 use crate::server::matchmaking::messages::{ServerWsMessage, ClientWsMessage, SessionKicked};
 use crate::server::matchmaking::types::WalletAddress;
 use crate::server::ws_error::{http_error_response, ws_session_kicked_message};
@@ -113,6 +113,14 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for MatchmakingSessio
                         });
                         self.anti_spam().reset_on_valid_action();
                     }
+                    ClientWsMessage::Practice { bots } => {
+                        self.matchmaking_addr.do_send(Practice {
+                            player_id: self.player_id.clone(),
+                            addr: ctx.address(),
+                            bots,
+                        });
+                        self.anti_spam().reset_on_valid_action();
+                    }
                     ClientWsMessage::Ping => {
                         debug!("[Matchmaking WS] Received Ping from wallet={}", self.player_id);
                         // Optionally, respond or ignore.
@@ -146,8 +154,8 @@ impl Handler<SessionKicked> for MatchmakingSession {
     type Result = ();
 
     /// Handles the session being kicked from the server.
-    fn handle(&mut self, _msg: SessionKicked, ctx: &mut Self::Context) -> Self::Result {
-        info!("[Matchmaking WS] Session kicked: wallet={}", self.player_id);
+    fn handle(&mut self, msg: SessionKicked, ctx: &mut Self::Context) -> Self::Result {
+        info!("[Matchmaking WS] Session kicked: wallet={} reason={}", self.player_id, msg.reason);
         ctx.text(ws_session_kicked_message(Some(json!({
             "wallet": self.player_id
         }))));
@@ -202,7 +210,7 @@ pub async fn ws_matchmaking(
             }
             (Some("username"), Some(name)) => {
                 username = urlencoding::decode(name)
-                    .unwrap_or_else(|_| Cow::Borrowed(""))
+                    .unwrap_or(Cow::Borrowed(""))
                     .into_owned();
             }
             _ => {}
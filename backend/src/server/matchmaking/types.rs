@@ -1,6 +1,8 @@
-/// Types used in the matchmaking module.
+//! Types used in the matchmaking module.
 
 use serde::{Serialize, Deserialize};
+use crate::game::types::{AgentKind, Position};
+use rand::seq::SliceRandom;
 
 /// Alias for a player's wallet address (unique identifier).
 pub type WalletAddress = String;
@@ -12,4 +14,38 @@ pub struct PlayerInfo {
     pub id: WalletAddress,
     /// Display username.
     pub username: String,
+    /// Team assigned by matchmaking when the game is created (`None` in the lobby).
+    /// Only team modes take it into account.
+    pub team_id: Option<u8>,
+    /// Spawn tile picked during the pre-game phase, used by the draft spawn strategy.
+    pub spawn_pick: Option<Position>,
+    /// Agent playing this seat, or `None` for a human player.
+    pub bot: Option<AgentKind>,
+}
+
+/// Seat a bot of the given kind in seat `seat` (from 1) of a game.
+pub fn bot_info(kind: AgentKind, seat: usize) -> PlayerInfo {
+    PlayerInfo {
+        id: format!("bot-{}", seat),
+        username: format!("{:?} {}", kind, seat),
+        team_id: None,
+        spawn_pick: None,
+        bot: Some(kind),
+    }
+}
+
+/// Split players into `team_count` teams whose sizes differ by at most one.
+///
+/// Players are shuffled first so teams change from one game to the next. With fewer than
+/// two teams, every player is left without a team.
+pub fn assign_teams(players: &mut [PlayerInfo], team_count: u8, rng: &mut impl rand::Rng) {
+    if team_count < 2 {
+        players.iter_mut().for_each(|p| p.team_id = None);
+        return;
+    }
+    let mut order: Vec<usize> = (0..players.len()).collect();
+    order.shuffle(rng);
+    for (slot, index) in order.into_iter().enumerate() {
+        players[index].team_id = Some(1 + (slot % team_count as usize) as u8);
+    }
 }
\ No newline at end of file
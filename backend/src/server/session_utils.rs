@@ -20,7 +20,7 @@ where
     K: Eq + Hash,
     A: PartialEq,
 {
-    map.get(key).map_or(false, |value| addr_extractor(value) == addr)
+    map.get(key).is_some_and(|value| addr_extractor(value) == addr)
 }
 
 /// Fonction générique pour récupérer une valeur si l'adresse correspond à celle enregistrée.
@@ -38,16 +38,16 @@ pub fn ws_error_message<S: AsRef<str>>(code: S, message: S, context: Option<Valu
     result
 }
 
-/// Example usage for a temporary ban:
-/// ```
-/// let ban_remaining_secs = 42;
-/// let wallet = "0x123...";
-/// let context = json!({
-///     "wallet": wallet,
-///     "ban_remaining_secs": ban_remaining_secs
-/// });
-/// let msg = ws_error_message("BANNED", "You have been banned for spamming. Please try again later.", Some(context));
-/// ```
+// Example usage for a temporary ban:
+// ```
+// let ban_remaining_secs = 42;
+// let wallet = "0x123...";
+// let context = json!({
+//     "wallet": wallet,
+//     "ban_remaining_secs": ban_remaining_secs
+// });
+// let msg = ws_error_message("BANNED", "You have been banned for spamming. Please try again later.", Some(context));
+// ```
 
 /// Returns a WebSocket message for session kicked (unicity violation).
 pub fn ws_session_kicked_message(context: Option<Value>) -> String {